@@ -45,12 +45,21 @@ make_ffi_enum!("Defines the format of each sample (mono, stereo, 8-bit, 16-bit,
 	#[doc = "16-bit, signed, mono samples. Little-endian"]
 	SixteenBitMono,
 	#[doc = "16-bit, signed, stereo samples. Little-endian. Left, then Right"]
-	SixteenBitStereo
+	SixteenBitStereo,
+	#[doc = "24-bit, signed, stereo samples. Each sample is stored in the low"]
+	#[doc = "three bytes of a little-endian 32-bit word (the top byte is"]
+	#[doc = "ignored). Left, then Right"]
+	TwentyFourBitStereo,
+	#[doc = "32-bit, IEEE 754 floating point (`f32`), stereo samples, from `-1.0`"]
+	#[doc = "to `1.0`. Little-endian. Left, then Right"]
+	ThirtyTwoBitFloatStereo
 });
 
 /// Configuration for an Audio Output or Input
 #[repr(C)]
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Config {
 	/// What format are the samples
 	pub sample_format: FfiSampleFormat,
@@ -94,13 +103,104 @@ pub struct MixerChannelInfo {
 	/// What is the current volume level for this Audio Mixer Channel, on a
 	/// scale of `0` to `max_level`. A value of `0` mutes the channel.
 	pub current_level: u8,
+	/// Is this Audio Mixer Channel currently muted?
+	///
+	/// Muting a channel does not change its `current_level`, so it returns
+	/// to the same volume when un-muted.
+	pub muted: bool,
 }
 
 // ============================================================================
 // Impls
 // ============================================================================
 
-// None
+impl SampleFormat {
+	/// How many bytes does one sample, for a single channel, take up?
+	pub const fn bytes_per_sample(self) -> usize {
+		match self {
+			SampleFormat::EightBitMono | SampleFormat::EightBitStereo => 1,
+			SampleFormat::SixteenBitMono | SampleFormat::SixteenBitStereo => 2,
+			SampleFormat::TwentyFourBitStereo | SampleFormat::ThirtyTwoBitFloatStereo => 4,
+		}
+	}
+
+	/// How many channels of audio are there?
+	pub const fn channels(self) -> u8 {
+		match self {
+			SampleFormat::EightBitMono | SampleFormat::SixteenBitMono => 1,
+			SampleFormat::EightBitStereo
+			| SampleFormat::SixteenBitStereo
+			| SampleFormat::TwentyFourBitStereo
+			| SampleFormat::ThirtyTwoBitFloatStereo => 2,
+		}
+	}
+
+	/// Is this a stereo (two channel) format?
+	pub const fn is_stereo(self) -> bool {
+		self.channels() == 2
+	}
+
+	/// How many bytes does one frame (one sample for every channel) take up?
+	pub const fn bytes_per_frame(self) -> usize {
+		self.bytes_per_sample() * self.channels() as usize
+	}
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for MixerChannelInfo {
+	fn format(&self, f: defmt::Formatter) {
+		defmt::write!(
+			f,
+			"MixerChannelInfo {{ name: {=str}, direction: {}, max_level: {}, current_level: {}, muted: {} }}",
+			self.name.as_str(),
+			self.direction,
+			self.max_level,
+			self.current_level,
+			self.muted
+		)
+	}
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn sample_sizes() {
+		let cases = [
+			(SampleFormat::EightBitMono, 1, 1, false),
+			(SampleFormat::EightBitStereo, 1, 2, true),
+			(SampleFormat::SixteenBitMono, 2, 1, false),
+			(SampleFormat::SixteenBitStereo, 2, 2, true),
+			(SampleFormat::TwentyFourBitStereo, 4, 2, true),
+			(SampleFormat::ThirtyTwoBitFloatStereo, 4, 2, true),
+		];
+		for (format, bytes, channels, stereo) in cases.iter() {
+			assert_eq!(format.bytes_per_sample(), *bytes);
+			assert_eq!(format.channels(), *channels);
+			assert_eq!(format.is_stereo(), *stereo);
+		}
+	}
+
+	#[test]
+	fn bytes_per_frame() {
+		let cases = [
+			(SampleFormat::EightBitMono, 1),
+			(SampleFormat::EightBitStereo, 2),
+			(SampleFormat::SixteenBitMono, 2),
+			(SampleFormat::SixteenBitStereo, 4),
+			(SampleFormat::TwentyFourBitStereo, 8),
+			(SampleFormat::ThirtyTwoBitFloatStereo, 8),
+		];
+		for (format, bytes) in cases.iter() {
+			assert_eq!(format.bytes_per_frame(), *bytes);
+		}
+	}
+}
 
 // ============================================================================
 // End of File
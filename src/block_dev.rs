@@ -44,7 +44,10 @@ DeviceType, FfiDeviceType, {
 	#[doc = "A floppy disk in a floppy disk drive"]
 	FloppyDiskDrive,
 	#[doc = "A compact flash card"]
-	CompactFlashCard
+	CompactFlashCard,
+	#[doc = "An optical disc (e.g. a CD-ROM) in an optical drive. Data discs"]
+	#[doc = "have 2048 byte blocks. See `Api::block_dev_read_toc`."]
+	OpticalDisc
 });
 
 /// Information about a block device.
@@ -57,6 +60,8 @@ pub struct DeviceInfo {
 	/// The kind of block device this is.
 	pub device_type: FfiDeviceType,
 	/// The size of an addressable block, in bytes.
+	///
+	/// This is typically 512 bytes, or 2048 bytes for an optical data disc.
 	pub block_size: u32,
 	/// The total number of addressable blocks.
 	pub num_blocks: u64,
@@ -73,13 +78,166 @@ pub struct DeviceInfo {
 /// Uniquely represents a block on a block device.
 #[repr(C)]
 #[derive(Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BlockIdx(pub u64);
 
 // ============================================================================
 // Impls
 // ============================================================================
 
-// None
+impl BlockIdx {
+	/// Find the block which contains the given byte offset.
+	///
+	/// Returns the block, and the offset of the byte within that block.
+	///
+	/// # Panics
+	///
+	/// Panics if `block_size` is zero.
+	#[inline]
+	pub const fn from_byte_offset(offset: u64, block_size: u32) -> (BlockIdx, u32) {
+		let block_size = block_size as u64;
+		(BlockIdx(offset / block_size), (offset % block_size) as u32)
+	}
+
+	/// Get the byte offset of the start of this block.
+	///
+	/// Returns `None` if the offset is too large to represent.
+	#[inline]
+	pub const fn to_byte_offset(self, block_size: u32) -> Option<u64> {
+		self.0.checked_mul(block_size as u64)
+	}
+
+	/// An alias for [`BlockIdx::to_byte_offset`].
+	#[inline]
+	pub const fn as_byte_offset(self, block_size: u32) -> Option<u64> {
+		self.to_byte_offset(block_size)
+	}
+
+	/// Move forwards (or backwards, if `blocks` is negative) by some number
+	/// of blocks.
+	///
+	/// Saturates at `BlockIdx(0)` and `BlockIdx(u64::MAX)`.
+	#[inline]
+	pub const fn offset(self, blocks: i64) -> BlockIdx {
+		if blocks >= 0 {
+			BlockIdx(self.0.saturating_add(blocks as u64))
+		} else {
+			BlockIdx(self.0.saturating_sub(blocks.unsigned_abs()))
+		}
+	}
+}
+
+impl core::ops::Add<u64> for BlockIdx {
+	type Output = BlockIdx;
+
+	/// Move forwards by some number of blocks, saturating at
+	/// `BlockIdx(u64::MAX)`.
+	#[inline]
+	fn add(self, rhs: u64) -> BlockIdx {
+		BlockIdx(self.0.saturating_add(rhs))
+	}
+}
+
+impl core::ops::Sub<u64> for BlockIdx {
+	type Output = BlockIdx;
+
+	/// Move backwards by some number of blocks, saturating at `BlockIdx(0)`.
+	#[inline]
+	fn sub(self, rhs: u64) -> BlockIdx {
+		BlockIdx(self.0.saturating_sub(rhs))
+	}
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for DeviceInfo {
+	fn format(&self, f: defmt::Formatter) {
+		defmt::write!(
+			f,
+			"DeviceInfo {{ name: {=str}, device_type: {}, block_size: {}, num_blocks: {}, ejectable: {}, removable: {}, media_present: {}, read_only: {} }}",
+			self.name.as_str(),
+			self.device_type,
+			self.block_size,
+			self.num_blocks,
+			self.ejectable,
+			self.removable,
+			self.media_present,
+			self.read_only
+		)
+	}
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn device_type_round_trip() {
+		for device_type in [
+			DeviceType::SecureDigitalCard,
+			DeviceType::HardDiskDrive,
+			DeviceType::FloppyDiskDrive,
+			DeviceType::CompactFlashCard,
+			DeviceType::OpticalDisc,
+		] {
+			let ffi = device_type.make_ffi_safe();
+			assert_eq!(ffi.make_safe().unwrap(), device_type);
+		}
+		assert!(FfiDeviceType(5).make_safe().is_err());
+	}
+
+	#[test]
+	fn byte_offset_start() {
+		assert_eq!(BlockIdx::from_byte_offset(0, 512), (BlockIdx(0), 0));
+		assert_eq!(BlockIdx(0).to_byte_offset(512), Some(0));
+	}
+
+	#[test]
+	fn byte_offset_across_boundary() {
+		assert_eq!(BlockIdx::from_byte_offset(511, 512), (BlockIdx(0), 511));
+		assert_eq!(BlockIdx::from_byte_offset(512, 512), (BlockIdx(1), 0));
+		assert_eq!(BlockIdx::from_byte_offset(1300, 512), (BlockIdx(2), 276));
+		assert_eq!(BlockIdx(2).to_byte_offset(512), Some(1300 - 276));
+	}
+
+	#[test]
+	fn byte_offset_large() {
+		// An offset in the middle of a 16 TiB disk
+		let offset = 8 * 1024 * 1024 * 1024 * 1024 + 3;
+		let (block, remainder) = BlockIdx::from_byte_offset(offset, 4096);
+		assert_eq!(block, BlockIdx(2 * 1024 * 1024 * 1024));
+		assert_eq!(remainder, 3);
+		assert_eq!(block.to_byte_offset(4096), Some(offset - 3));
+		assert_eq!(BlockIdx(u64::MAX).to_byte_offset(512), None);
+		assert_eq!(
+			BlockIdx(u64::MAX / 512).to_byte_offset(512),
+			Some(u64::MAX - 511)
+		);
+	}
+
+	#[test]
+	fn add_sub() {
+		assert_eq!(BlockIdx(10) + 5, BlockIdx(15));
+		assert_eq!(BlockIdx(10) - 5, BlockIdx(5));
+		assert_eq!(BlockIdx(u64::MAX - 1) + 5, BlockIdx(u64::MAX));
+		assert_eq!(BlockIdx(3) - 5, BlockIdx(0));
+		assert_eq!((BlockIdx(100) + 4).to_byte_offset(512), Some(104 * 512));
+	}
+
+	#[test]
+	fn offset() {
+		assert_eq!(BlockIdx(10).offset(5), BlockIdx(15));
+		assert_eq!(BlockIdx(10).offset(-5), BlockIdx(5));
+		assert_eq!(BlockIdx(10).offset(0), BlockIdx(10));
+		assert_eq!(BlockIdx(3).offset(-5), BlockIdx(0));
+		assert_eq!(BlockIdx(3).offset(i64::MIN), BlockIdx(0));
+		assert_eq!(BlockIdx(u64::MAX).offset(i64::MAX), BlockIdx(u64::MAX));
+	}
+}
 
 // ============================================================================
 // End of File
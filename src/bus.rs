@@ -2,6 +2,11 @@
 //!
 //! Neotron Bus related types.
 //!
+//! Each Neotron Bus Peripheral may have an interrupt line, which appears as
+//! one bit in the value returned by `Api::bus_interrupt_status`. The
+//! `interrupt_bit` field of [`PeripheralInfo`] says which bit that is, so
+//! the OS doesn't need to know how a particular system is wired.
+//!
 //! Note that all types in this file that are exported in the `Api` structure
 //! *must* be `#[repr(C)]` and ABI stable.
 
@@ -49,6 +54,22 @@ make_ffi_enum!("The kinds of Peripheral you can put on a Neotron Bus",
 	Reserved
 });
 
+make_ffi_enum!("The SPI clock polarity (CPOL) and phase (CPHA) to use on the Neotron Bus",
+	SpiMode, FfiSpiMode, {
+	#[doc = "CPOL = 0, CPHA = 0. The clock idles low, and data is sampled on the"]
+	#[doc = "rising edge. This is the most common mode, and the one SD Cards use."]
+	Mode0,
+	#[doc = "CPOL = 0, CPHA = 1. The clock idles low, and data is sampled on the"]
+	#[doc = "falling edge."]
+	Mode1,
+	#[doc = "CPOL = 1, CPHA = 0. The clock idles high, and data is sampled on the"]
+	#[doc = "falling edge."]
+	Mode2,
+	#[doc = "CPOL = 1, CPHA = 1. The clock idles high, and data is sampled on the"]
+	#[doc = "rising edge."]
+	Mode3
+});
+
 /// Describes a Neotron Bus Peripheral
 #[repr(C)]
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -57,13 +78,27 @@ pub struct PeripheralInfo {
 	pub name: crate::FfiString<'static>,
 	/// The kind of peripheral
 	pub kind: FfiPeripheralKind,
+	/// Which bit in the `Api::bus_interrupt_status` value is this
+	/// peripheral's interrupt, or `None` if it doesn't have one.
+	pub interrupt_bit: crate::FfiOption<u8>,
 }
 
 // ============================================================================
 // Impls
 // ============================================================================
 
-// None
+#[cfg(feature = "defmt")]
+impl defmt::Format for PeripheralInfo {
+	fn format(&self, f: defmt::Formatter) {
+		defmt::write!(
+			f,
+			"PeripheralInfo {{ name: {=str}, kind: {}, interrupt_bit: {} }}",
+			self.name.as_str(),
+			self.kind,
+			core::option::Option::<u8>::from(self.interrupt_bit.clone())
+		)
+	}
+}
 
 // ============================================================================
 // End of File
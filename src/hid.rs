@@ -26,11 +26,58 @@
 
 pub use pc_keyboard::KeyCode;
 
+use crate::make_ffi_enum;
+
 // ============================================================================
 // Constants
 // ============================================================================
 
-// None
+/// Every [`KeyCode`], in the order they are numbered in the encoded form of
+/// a [`HidEvent`].
+///
+/// A key code is encoded as its position in this table, not as its
+/// discriminant, so the encoding does not change if `pc-keyboard` adds or
+/// reorders its variants. This table is append-only - new key codes go on
+/// the end, and existing entries must never be moved or removed. The
+/// `key_codes` test checks that no key code from `pc-keyboard` is missing.
+#[rustfmt::skip]
+const KEY_CODES: &[KeyCode] = &[
+	// Row 1 (the F-keys)
+	KeyCode::Escape, KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6,
+	KeyCode::F7, KeyCode::F8, KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12,
+	KeyCode::PrintScreen, KeyCode::SysRq, KeyCode::ScrollLock, KeyCode::PauseBreak,
+	// Row 2 (the numbers)
+	KeyCode::Oem8, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4, KeyCode::Key5,
+	KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9, KeyCode::Key0, KeyCode::OemMinus,
+	KeyCode::OemPlus, KeyCode::Backspace, KeyCode::Insert, KeyCode::Home, KeyCode::PageUp,
+	KeyCode::NumpadLock, KeyCode::NumpadDivide, KeyCode::NumpadMultiply, KeyCode::NumpadSubtract,
+	// Row 3 (QWERTY)
+	KeyCode::Tab, KeyCode::Q, KeyCode::W, KeyCode::E, KeyCode::R, KeyCode::T, KeyCode::Y,
+	KeyCode::U, KeyCode::I, KeyCode::O, KeyCode::P, KeyCode::Oem4, KeyCode::Oem6, KeyCode::Oem5,
+	KeyCode::Oem7, KeyCode::Delete, KeyCode::End, KeyCode::PageDown, KeyCode::Numpad7,
+	KeyCode::Numpad8, KeyCode::Numpad9, KeyCode::NumpadAdd,
+	// Row 4 (ASDF)
+	KeyCode::CapsLock, KeyCode::A, KeyCode::S, KeyCode::D, KeyCode::F, KeyCode::G, KeyCode::H,
+	KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::Oem1, KeyCode::Oem3, KeyCode::Return,
+	KeyCode::Numpad4, KeyCode::Numpad5, KeyCode::Numpad6,
+	// Row 5 (ZXCV)
+	KeyCode::LShift, KeyCode::Z, KeyCode::X, KeyCode::C, KeyCode::V, KeyCode::B, KeyCode::N,
+	KeyCode::M, KeyCode::OemComma, KeyCode::OemPeriod, KeyCode::Oem2, KeyCode::RShift,
+	KeyCode::ArrowUp, KeyCode::Numpad1, KeyCode::Numpad2, KeyCode::Numpad3, KeyCode::NumpadEnter,
+	// Row 6 (modifiers and space bar)
+	KeyCode::LControl, KeyCode::LWin, KeyCode::LAlt, KeyCode::Spacebar, KeyCode::RAltGr,
+	KeyCode::RWin, KeyCode::Apps, KeyCode::RControl, KeyCode::ArrowLeft, KeyCode::ArrowDown,
+	KeyCode::ArrowRight, KeyCode::Numpad0, KeyCode::NumpadPeriod,
+	// JIS 109-key extra keys
+	KeyCode::Oem9, KeyCode::Oem10, KeyCode::Oem11, KeyCode::Oem12, KeyCode::Oem13,
+	// Extra keys
+	KeyCode::PrevTrack, KeyCode::NextTrack, KeyCode::Mute, KeyCode::Calculator, KeyCode::Play,
+	KeyCode::Stop, KeyCode::VolumeDown, KeyCode::VolumeUp, KeyCode::WWWHome, KeyCode::PowerOnTestOk,
+	KeyCode::TooManyKeys, KeyCode::RControl2, KeyCode::RAlt2,
+];
+
+// The encoded form of a `HidEvent` only has one byte for a key code.
+const _: () = assert!(KEY_CODES.len() <= 256);
 
 // ============================================================================
 // Types
@@ -48,12 +95,57 @@ pub enum HidEvent {
 	///
 	/// Or these may be generated periodically even if there was no movement or clicking.
 	MouseInput(MouseData),
+	/// A gamepad (or joystick) was moved or had a button pressed or released.
+	GamepadInput(GamepadData),
+	/// An absolute pointing device (such as a touchscreen) was moved, touched
+	/// or had a button pressed or released.
+	AbsolutePointer(PointerData),
+	/// A raw keyboard scancode was received.
+	///
+	/// These are only sent when raw mode is enabled with
+	/// `Api::hid_set_raw_mode`. For a PS/2 keyboard, `code` is from Scan Code
+	/// Set 2, with any `0xE0` prefix in the top byte. For a USB keyboard, it
+	/// is the HID Usage ID from the Keyboard/Keypad page.
+	RawScancode {
+		/// The scancode
+		code: u16,
+		/// `true` if the key was pressed (a *make* code), `false` if it was
+		/// released (a *break* code)
+		make: bool,
+	},
+}
+
+make_ffi_enum!("The kinds of Human Interface Device the BIOS can report.",
+	DeviceKind, FfiDeviceKind, {
+	#[doc = "A keyboard, which generates `KeyPress` and `KeyRelease` events."]
+	Keyboard,
+	#[doc = "A mouse, which generates `MouseInput` events."]
+	Mouse,
+	#[doc = "A gamepad or joystick, which generates `GamepadInput` events."]
+	Gamepad,
+	#[doc = "A touchpad."]
+	Touchpad
+});
+
+/// Information about a particular Human Interface Device.
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeviceInfo {
+	/// Some human-readable name for this device (e.g. `PS/2 Keyboard` or
+	/// `USB Mouse`)
+	pub name: crate::FfiString<'static>,
+	/// The kind of this device
+	pub kind: FfiDeviceKind,
+	/// Is the device currently plugged in?
+	pub connected: bool,
 }
 
 /// Represents the movement of a mouse over the previous period of time, and
 /// the current state of the mouse buttons.
 #[repr(C)]
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MouseData {
 	/// How far the mouse moved left(-ve)/right(+ve) since the last request
 	pub x: i16,
@@ -61,22 +153,284 @@ pub struct MouseData {
 	pub y: i16,
 	/// The current state of the mouse buttons.
 	pub buttons: MouseButtons,
+	/// How many detents the scroll wheel moved towards the user(-ve)/away
+	/// from the user(+ve) since the last request
+	pub wheel: i8,
+}
+
+/// Represents the state of an absolute pointing device, such as a touchscreen
+/// or a graphics tablet.
+///
+/// The position is given in a normalised co-ordinate space, which runs from
+/// `0` at the left (or top) edge to `65535` at the right (or bottom) edge,
+/// whatever the resolution of the device or of the current video mode. The
+/// BIOS scales each physical reading from the device's own range (which it
+/// may have to calibrate) into this range. Use [`PointerData::position_in`]
+/// to convert it into screen co-ordinates.
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointerData {
+	/// The normalised horizontal position, from left (`0`) to right
+	/// (`65535`)
+	pub x: u16,
+	/// The normalised vertical position, from top (`0`) to bottom (`65535`)
+	pub y: u16,
+	/// How hard the pointer is being pressed, from `0` (not touching) to
+	/// `65535`. Devices which cannot measure pressure report `0` or `65535`.
+	pub pressure: u16,
+	/// The current state of the pointer's buttons. For a touchscreen, a touch
+	/// is reported as the left button.
+	pub buttons: MouseButtons,
 }
 
 /// Represents the buttons on a mouse.
 #[repr(transparent)]
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MouseButtons(u8);
 
+/// Represents the current state of a gamepad (or joystick).
+///
+/// The analog sticks go from `-128` to `127`, where zero is the centre
+/// position. A gamepad with no analog sticks will always report zero.
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GamepadData {
+	/// Which gamepad this is.
+	///
+	/// The BIOS numbers gamepads from zero, in whatever order it finds them.
+	pub port: u8,
+	/// The current state of the gamepad buttons.
+	pub buttons: GamepadButtons,
+	/// The position of the left stick, left(-ve)/right(+ve)
+	pub left_x: i8,
+	/// The position of the left stick, up(-ve)/down(+ve)
+	pub left_y: i8,
+	/// The position of the right stick, left(-ve)/right(+ve)
+	pub right_x: i8,
+	/// The position of the right stick, up(-ve)/down(+ve)
+	pub right_y: i8,
+}
+
+/// Represents the buttons on a gamepad.
+#[repr(transparent)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GamepadButtons(u16);
+
+/// Represents which keyboard modifier keys are held down, and which lock
+/// keys are active.
+#[repr(transparent)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Modifiers(u16);
+
 /// Represents the LEDs on a keyboard.
 #[repr(transparent)]
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct KeyboardLeds(u8);
 
 // ============================================================================
 // Impls
 // ============================================================================
 
+impl HidEvent {
+	const TAG_KEY_PRESS: u8 = 1;
+	const TAG_KEY_RELEASE: u8 = 2;
+	const TAG_MOUSE_INPUT: u8 = 3;
+	const TAG_GAMEPAD_INPUT: u8 = 4;
+	const TAG_RAW_SCANCODE: u8 = 5;
+	const TAG_ABSOLUTE_POINTER: u8 = 6;
+
+	/// The most bytes that [`HidEvent::encode`] will write.
+	pub const MAX_ENCODED_LEN: usize = 8;
+
+	/// Encode this event into a compact byte format.
+	///
+	/// Unlike the in-memory layout, this format does not depend on the
+	/// compiler or on the version of any crate, so it is suitable for
+	/// sending events over a network, or recording them to a file. Each
+	/// event is a one byte tag, followed by a payload:
+	///
+	/// | Tag | Event             | Payload                                            |
+	/// |-----|-------------------|----------------------------------------------------|
+	/// | `1` | `KeyPress`        | `code: u8`                                         |
+	/// | `2` | `KeyRelease`      | `code: u8`                                         |
+	/// | `3` | `MouseInput`      | `x: i16`, `y: i16`, `buttons: u8`, `wheel: i8`     |
+	/// | `4` | `GamepadInput`    | `port: u8`, `buttons: u16`, `sticks: [i8; 4]`      |
+	/// | `5` | `RawScancode`     | `code: u16`, `make: u8`                            |
+	/// | `6` | `AbsolutePointer` | `x: u16`, `y: u16`, `pressure: u16`, `buttons: u8` |
+	///
+	/// Multi-byte values are little-endian. The gamepad `sticks` are in the
+	/// order `left_x`, `left_y`, `right_x`, `right_y`. A raw scancode's `make`
+	/// byte is `1` for a key press and `0` for a key release.
+	///
+	/// Key codes are numbered from zero, in the order of the keys on a
+	/// 105-key keyboard, row by row - so `Escape` is `0`, `Key1` is `18`, `A`
+	/// is `61`, `Spacebar` is `96`, and so on - followed by the JIS and
+	/// other extra keys. These numbers never change. A key code added to
+	/// `pc-keyboard` since this crate was written cannot be encoded.
+	///
+	/// Returns the number of bytes written, or `None` if `out` is too small
+	/// or the event cannot be encoded.
+	pub fn encode(&self, out: &mut [u8]) -> Option<usize> {
+		let mut buffer = [0u8; Self::MAX_ENCODED_LEN];
+		let len = match self {
+			HidEvent::KeyPress(code) => {
+				buffer[0] = Self::TAG_KEY_PRESS;
+				buffer[1] = key_code_to_u8(*code)?;
+				2
+			}
+			HidEvent::KeyRelease(code) => {
+				buffer[0] = Self::TAG_KEY_RELEASE;
+				buffer[1] = key_code_to_u8(*code)?;
+				2
+			}
+			HidEvent::MouseInput(data) => {
+				buffer[0] = Self::TAG_MOUSE_INPUT;
+				buffer[1..3].copy_from_slice(&data.x.to_le_bytes());
+				buffer[3..5].copy_from_slice(&data.y.to_le_bytes());
+				buffer[5] = data.buttons.0;
+				buffer[6] = data.wheel as u8;
+				7
+			}
+			HidEvent::GamepadInput(data) => {
+				buffer[0] = Self::TAG_GAMEPAD_INPUT;
+				buffer[1] = data.port;
+				buffer[2..4].copy_from_slice(&data.buttons.0.to_le_bytes());
+				buffer[4] = data.left_x as u8;
+				buffer[5] = data.left_y as u8;
+				buffer[6] = data.right_x as u8;
+				buffer[7] = data.right_y as u8;
+				8
+			}
+			HidEvent::AbsolutePointer(data) => {
+				buffer[0] = Self::TAG_ABSOLUTE_POINTER;
+				buffer[1..3].copy_from_slice(&data.x.to_le_bytes());
+				buffer[3..5].copy_from_slice(&data.y.to_le_bytes());
+				buffer[5..7].copy_from_slice(&data.pressure.to_le_bytes());
+				buffer[7] = data.buttons.0;
+				8
+			}
+			HidEvent::RawScancode { code, make } => {
+				buffer[0] = Self::TAG_RAW_SCANCODE;
+				buffer[1..3].copy_from_slice(&code.to_le_bytes());
+				buffer[3] = u8::from(*make);
+				4
+			}
+		};
+		out.get_mut(0..len)?.copy_from_slice(&buffer[0..len]);
+		Some(len)
+	}
+
+	/// Decode an event previously encoded with [`HidEvent::encode`].
+	///
+	/// Returns the event and the number of bytes consumed, or `None` if the
+	/// bytes are truncated or do not contain a valid event.
+	pub fn decode(bytes: &[u8]) -> Option<(HidEvent, usize)> {
+		match *bytes.first()? {
+			Self::TAG_KEY_PRESS => {
+				let code = key_code_from_u8(*bytes.get(1)?)?;
+				Some((HidEvent::KeyPress(code), 2))
+			}
+			Self::TAG_KEY_RELEASE => {
+				let code = key_code_from_u8(*bytes.get(1)?)?;
+				Some((HidEvent::KeyRelease(code), 2))
+			}
+			Self::TAG_MOUSE_INPUT => {
+				let payload = bytes.get(1..7)?;
+				let data = MouseData::new(
+					i16::from_le_bytes([payload[0], payload[1]]),
+					i16::from_le_bytes([payload[2], payload[3]]),
+					MouseButtons(payload[4]),
+					payload[5] as i8,
+				);
+				Some((HidEvent::MouseInput(data), 7))
+			}
+			Self::TAG_GAMEPAD_INPUT => {
+				let payload = bytes.get(1..8)?;
+				let data = GamepadData::new(
+					payload[0],
+					GamepadButtons(u16::from_le_bytes([payload[1], payload[2]])),
+					(payload[3] as i8, payload[4] as i8),
+					(payload[5] as i8, payload[6] as i8),
+				);
+				Some((HidEvent::GamepadInput(data), 8))
+			}
+			Self::TAG_ABSOLUTE_POINTER => {
+				let payload = bytes.get(1..8)?;
+				let data = PointerData::new(
+					u16::from_le_bytes([payload[0], payload[1]]),
+					u16::from_le_bytes([payload[2], payload[3]]),
+					u16::from_le_bytes([payload[4], payload[5]]),
+					MouseButtons(payload[6]),
+				);
+				Some((HidEvent::AbsolutePointer(data), 8))
+			}
+			Self::TAG_RAW_SCANCODE => {
+				let payload = bytes.get(1..4)?;
+				let code = u16::from_le_bytes([payload[0], payload[1]]);
+				let make = match payload[2] {
+					0 => false,
+					1 => true,
+					_ => return None,
+				};
+				Some((HidEvent::RawScancode { code, make }, 4))
+			}
+			_ => None,
+		}
+	}
+}
+
+impl MouseData {
+	/// Create a new `MouseData` value.
+	pub const fn new(x: i16, y: i16, buttons: MouseButtons, wheel: i8) -> Self {
+		Self {
+			x,
+			y,
+			buttons,
+			wheel,
+		}
+	}
+}
+
+impl PointerData {
+	/// Create a new `PointerData` value.
+	pub const fn new(x: u16, y: u16, pressure: u16, buttons: MouseButtons) -> Self {
+		Self {
+			x,
+			y,
+			pressure,
+			buttons,
+		}
+	}
+
+	/// Is anything touching the device?
+	pub const fn is_touching(&self) -> bool {
+		self.pressure != 0
+	}
+
+	/// Convert the normalised position into a position on a screen which is
+	/// `width` by `height` pixels.
+	///
+	/// The result is always on the screen (unless it has zero width or
+	/// height).
+	pub const fn position_in(&self, width: u16, height: u16) -> (u16, u16) {
+		let x = (self.x as u32 * width as u32) >> 16;
+		let y = (self.y as u32 * height as u32) >> 16;
+		(x as u16, y as u16)
+	}
+}
+
 impl MouseButtons {
 	const LEFT_BIT: u8 = 1 << 0;
 	const MIDDLE_BIT: u8 = 1 << 1;
@@ -129,6 +483,365 @@ impl Default for MouseButtons {
 	}
 }
 
+impl GamepadData {
+	/// Create a new `GamepadData` value.
+	///
+	/// The sticks are given as `(x, y)` pairs.
+	pub const fn new(
+		port: u8,
+		buttons: GamepadButtons,
+		left_stick: (i8, i8),
+		right_stick: (i8, i8),
+	) -> Self {
+		Self {
+			port,
+			buttons,
+			left_x: left_stick.0,
+			left_y: left_stick.1,
+			right_x: right_stick.0,
+			right_y: right_stick.1,
+		}
+	}
+
+	/// Get the position of the left stick, as an `(x, y)` pair.
+	pub const fn left_stick(self) -> (i8, i8) {
+		(self.left_x, self.left_y)
+	}
+
+	/// Get the position of the right stick, as an `(x, y)` pair.
+	pub const fn right_stick(self) -> (i8, i8) {
+		(self.right_x, self.right_y)
+	}
+}
+
+impl GamepadButtons {
+	const A_BIT: u16 = 1 << 0;
+	const B_BIT: u16 = 1 << 1;
+	const X_BIT: u16 = 1 << 2;
+	const Y_BIT: u16 = 1 << 3;
+	const START_BIT: u16 = 1 << 4;
+	const SELECT_BIT: u16 = 1 << 5;
+	const L_BIT: u16 = 1 << 6;
+	const R_BIT: u16 = 1 << 7;
+	const UP_BIT: u16 = 1 << 8;
+	const DOWN_BIT: u16 = 1 << 9;
+	const LEFT_BIT: u16 = 1 << 10;
+	const RIGHT_BIT: u16 = 1 << 11;
+
+	/// Create a new `GamepadButtons` value.
+	///
+	/// All buttons default to *not pressed*
+	pub const fn new() -> Self {
+		Self(0)
+	}
+
+	/// Note that the A button is currently being pressed.
+	pub const fn set_a_pressed(self) -> Self {
+		let value = self.0 | Self::A_BIT;
+		Self(value)
+	}
+
+	/// Note that the B button is currently being pressed.
+	pub const fn set_b_pressed(self) -> Self {
+		let value = self.0 | Self::B_BIT;
+		Self(value)
+	}
+
+	/// Note that the X button is currently being pressed.
+	pub const fn set_x_pressed(self) -> Self {
+		let value = self.0 | Self::X_BIT;
+		Self(value)
+	}
+
+	/// Note that the Y button is currently being pressed.
+	pub const fn set_y_pressed(self) -> Self {
+		let value = self.0 | Self::Y_BIT;
+		Self(value)
+	}
+
+	/// Note that the Start button is currently being pressed.
+	pub const fn set_start_pressed(self) -> Self {
+		let value = self.0 | Self::START_BIT;
+		Self(value)
+	}
+
+	/// Note that the Select button is currently being pressed.
+	pub const fn set_select_pressed(self) -> Self {
+		let value = self.0 | Self::SELECT_BIT;
+		Self(value)
+	}
+
+	/// Note that the L button is currently being pressed.
+	pub const fn set_l_pressed(self) -> Self {
+		let value = self.0 | Self::L_BIT;
+		Self(value)
+	}
+
+	/// Note that the R button is currently being pressed.
+	pub const fn set_r_pressed(self) -> Self {
+		let value = self.0 | Self::R_BIT;
+		Self(value)
+	}
+
+	/// Note that the D-Pad Up button is currently being pressed.
+	pub const fn set_up_pressed(self) -> Self {
+		let value = self.0 | Self::UP_BIT;
+		Self(value)
+	}
+
+	/// Note that the D-Pad Down button is currently being pressed.
+	pub const fn set_down_pressed(self) -> Self {
+		let value = self.0 | Self::DOWN_BIT;
+		Self(value)
+	}
+
+	/// Note that the D-Pad Left button is currently being pressed.
+	pub const fn set_left_pressed(self) -> Self {
+		let value = self.0 | Self::LEFT_BIT;
+		Self(value)
+	}
+
+	/// Note that the D-Pad Right button is currently being pressed.
+	pub const fn set_right_pressed(self) -> Self {
+		let value = self.0 | Self::RIGHT_BIT;
+		Self(value)
+	}
+
+	/// Returns `true` if the A button is currently being pressed.
+	pub const fn is_a_pressed(self) -> bool {
+		self.0 & Self::A_BIT != 0
+	}
+
+	/// Returns `true` if the B button is currently being pressed.
+	pub const fn is_b_pressed(self) -> bool {
+		self.0 & Self::B_BIT != 0
+	}
+
+	/// Returns `true` if the X button is currently being pressed.
+	pub const fn is_x_pressed(self) -> bool {
+		self.0 & Self::X_BIT != 0
+	}
+
+	/// Returns `true` if the Y button is currently being pressed.
+	pub const fn is_y_pressed(self) -> bool {
+		self.0 & Self::Y_BIT != 0
+	}
+
+	/// Returns `true` if the Start button is currently being pressed.
+	pub const fn is_start_pressed(self) -> bool {
+		self.0 & Self::START_BIT != 0
+	}
+
+	/// Returns `true` if the Select button is currently being pressed.
+	pub const fn is_select_pressed(self) -> bool {
+		self.0 & Self::SELECT_BIT != 0
+	}
+
+	/// Returns `true` if the L button is currently being pressed.
+	pub const fn is_l_pressed(self) -> bool {
+		self.0 & Self::L_BIT != 0
+	}
+
+	/// Returns `true` if the R button is currently being pressed.
+	pub const fn is_r_pressed(self) -> bool {
+		self.0 & Self::R_BIT != 0
+	}
+
+	/// Returns `true` if the D-Pad Up button is currently being pressed.
+	pub const fn is_up_pressed(self) -> bool {
+		self.0 & Self::UP_BIT != 0
+	}
+
+	/// Returns `true` if the D-Pad Down button is currently being pressed.
+	pub const fn is_down_pressed(self) -> bool {
+		self.0 & Self::DOWN_BIT != 0
+	}
+
+	/// Returns `true` if the D-Pad Left button is currently being pressed.
+	pub const fn is_left_pressed(self) -> bool {
+		self.0 & Self::LEFT_BIT != 0
+	}
+
+	/// Returns `true` if the D-Pad Right button is currently being pressed.
+	pub const fn is_right_pressed(self) -> bool {
+		self.0 & Self::RIGHT_BIT != 0
+	}
+}
+
+impl Default for GamepadButtons {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Modifiers {
+	const LEFT_SHIFT_BIT: u16 = 1 << 0;
+	const RIGHT_SHIFT_BIT: u16 = 1 << 1;
+	const LEFT_CTRL_BIT: u16 = 1 << 2;
+	const RIGHT_CTRL_BIT: u16 = 1 << 3;
+	const LEFT_ALT_BIT: u16 = 1 << 4;
+	const RIGHT_ALT_BIT: u16 = 1 << 5;
+	const LEFT_GUI_BIT: u16 = 1 << 6;
+	const RIGHT_GUI_BIT: u16 = 1 << 7;
+	const CAPS_LOCK_BIT: u16 = 1 << 8;
+	const NUM_LOCK_BIT: u16 = 1 << 9;
+	const SCROLL_LOCK_BIT: u16 = 1 << 10;
+
+	/// Create a new `Modifiers` value.
+	///
+	/// All keys default to *not pressed*, and all locks to *off*.
+	pub const fn new() -> Self {
+		Self(0)
+	}
+
+	/// Note that the Left Shift key is currently being pressed.
+	pub const fn set_left_shift_pressed(self) -> Self {
+		let value = self.0 | Self::LEFT_SHIFT_BIT;
+		Self(value)
+	}
+
+	/// Note that the Right Shift key is currently being pressed.
+	pub const fn set_right_shift_pressed(self) -> Self {
+		let value = self.0 | Self::RIGHT_SHIFT_BIT;
+		Self(value)
+	}
+
+	/// Note that the Left Ctrl key is currently being pressed.
+	pub const fn set_left_ctrl_pressed(self) -> Self {
+		let value = self.0 | Self::LEFT_CTRL_BIT;
+		Self(value)
+	}
+
+	/// Note that the Right Ctrl key is currently being pressed.
+	pub const fn set_right_ctrl_pressed(self) -> Self {
+		let value = self.0 | Self::RIGHT_CTRL_BIT;
+		Self(value)
+	}
+
+	/// Note that the Left Alt key is currently being pressed.
+	pub const fn set_left_alt_pressed(self) -> Self {
+		let value = self.0 | Self::LEFT_ALT_BIT;
+		Self(value)
+	}
+
+	/// Note that the Right Alt key is currently being pressed.
+	pub const fn set_right_alt_pressed(self) -> Self {
+		let value = self.0 | Self::RIGHT_ALT_BIT;
+		Self(value)
+	}
+
+	/// Note that the Left GUI key is currently being pressed.
+	pub const fn set_left_gui_pressed(self) -> Self {
+		let value = self.0 | Self::LEFT_GUI_BIT;
+		Self(value)
+	}
+
+	/// Note that the Right GUI key is currently being pressed.
+	pub const fn set_right_gui_pressed(self) -> Self {
+		let value = self.0 | Self::RIGHT_GUI_BIT;
+		Self(value)
+	}
+
+	/// Note that Caps Lock is currently on.
+	pub const fn set_caps_lock_on(self) -> Self {
+		let value = self.0 | Self::CAPS_LOCK_BIT;
+		Self(value)
+	}
+
+	/// Note that Num Lock is currently on.
+	pub const fn set_num_lock_on(self) -> Self {
+		let value = self.0 | Self::NUM_LOCK_BIT;
+		Self(value)
+	}
+
+	/// Note that Scroll Lock is currently on.
+	pub const fn set_scroll_lock_on(self) -> Self {
+		let value = self.0 | Self::SCROLL_LOCK_BIT;
+		Self(value)
+	}
+
+	/// Returns `true` if the Left Shift key is currently being pressed.
+	pub const fn is_left_shift_pressed(self) -> bool {
+		self.0 & Self::LEFT_SHIFT_BIT != 0
+	}
+
+	/// Returns `true` if the Right Shift key is currently being pressed.
+	pub const fn is_right_shift_pressed(self) -> bool {
+		self.0 & Self::RIGHT_SHIFT_BIT != 0
+	}
+
+	/// Returns `true` if the Left Ctrl key is currently being pressed.
+	pub const fn is_left_ctrl_pressed(self) -> bool {
+		self.0 & Self::LEFT_CTRL_BIT != 0
+	}
+
+	/// Returns `true` if the Right Ctrl key is currently being pressed.
+	pub const fn is_right_ctrl_pressed(self) -> bool {
+		self.0 & Self::RIGHT_CTRL_BIT != 0
+	}
+
+	/// Returns `true` if the Left Alt key is currently being pressed.
+	pub const fn is_left_alt_pressed(self) -> bool {
+		self.0 & Self::LEFT_ALT_BIT != 0
+	}
+
+	/// Returns `true` if the Right Alt key is currently being pressed.
+	pub const fn is_right_alt_pressed(self) -> bool {
+		self.0 & Self::RIGHT_ALT_BIT != 0
+	}
+
+	/// Returns `true` if the Left GUI key is currently being pressed.
+	pub const fn is_left_gui_pressed(self) -> bool {
+		self.0 & Self::LEFT_GUI_BIT != 0
+	}
+
+	/// Returns `true` if the Right GUI key is currently being pressed.
+	pub const fn is_right_gui_pressed(self) -> bool {
+		self.0 & Self::RIGHT_GUI_BIT != 0
+	}
+
+	/// Returns `true` if either Shift key is currently being pressed.
+	pub const fn is_shift_pressed(self) -> bool {
+		self.is_left_shift_pressed() || self.is_right_shift_pressed()
+	}
+
+	/// Returns `true` if either Ctrl key is currently being pressed.
+	pub const fn is_ctrl_pressed(self) -> bool {
+		self.is_left_ctrl_pressed() || self.is_right_ctrl_pressed()
+	}
+
+	/// Returns `true` if either Alt key is currently being pressed.
+	pub const fn is_alt_pressed(self) -> bool {
+		self.is_left_alt_pressed() || self.is_right_alt_pressed()
+	}
+
+	/// Returns `true` if either GUI key is currently being pressed.
+	pub const fn is_gui_pressed(self) -> bool {
+		self.is_left_gui_pressed() || self.is_right_gui_pressed()
+	}
+
+	/// Returns `true` if Caps Lock is currently on.
+	pub const fn is_caps_lock_on(self) -> bool {
+		self.0 & Self::CAPS_LOCK_BIT != 0
+	}
+
+	/// Returns `true` if Num Lock is currently on.
+	pub const fn is_num_lock_on(self) -> bool {
+		self.0 & Self::NUM_LOCK_BIT != 0
+	}
+
+	/// Returns `true` if Scroll Lock is currently on.
+	pub const fn is_scroll_lock_on(self) -> bool {
+		self.0 & Self::SCROLL_LOCK_BIT != 0
+	}
+}
+
+impl Default for Modifiers {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
 impl KeyboardLeds {
 	const CAPS_LOCK_BIT: u8 = 1 << 0;
 	const SCROLL_LOCK_BIT: u8 = 1 << 1;
@@ -181,6 +894,249 @@ impl Default for KeyboardLeds {
 	}
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for HidEvent {
+	fn format(&self, f: defmt::Formatter) {
+		// `KeyCode` comes from `pc-keyboard`, which doesn't support defmt
+		match self {
+			HidEvent::KeyPress(code) => {
+				defmt::write!(f, "KeyPress({})", defmt::Debug2Format(code))
+			}
+			HidEvent::KeyRelease(code) => {
+				defmt::write!(f, "KeyRelease({})", defmt::Debug2Format(code))
+			}
+			HidEvent::MouseInput(data) => defmt::write!(f, "MouseInput({})", data),
+			HidEvent::GamepadInput(data) => defmt::write!(f, "GamepadInput({})", data),
+			HidEvent::AbsolutePointer(data) => defmt::write!(f, "AbsolutePointer({})", data),
+			HidEvent::RawScancode { code, make } => {
+				defmt::write!(f, "RawScancode {{ code: {=u16:#x}, make: {} }}", code, make)
+			}
+		}
+	}
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for DeviceInfo {
+	fn format(&self, f: defmt::Formatter) {
+		defmt::write!(
+			f,
+			"DeviceInfo {{ name: {=str}, kind: {}, connected: {} }}",
+			self.name.as_str(),
+			self.kind,
+			self.connected
+		)
+	}
+}
+
+// ============================================================================
+// Functions
+// ============================================================================
+
+/// Get the encoded form of a [`KeyCode`] - its position in [`KEY_CODES`].
+fn key_code_to_u8(code: KeyCode) -> Option<u8> {
+	let position = KEY_CODES.iter().position(|entry| *entry == code)?;
+	Some(position as u8)
+}
+
+/// Convert the encoded form of a [`KeyCode`] back into a [`KeyCode`].
+fn key_code_from_u8(value: u8) -> Option<KeyCode> {
+	KEY_CODES.get(usize::from(value)).copied()
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn mouse_data_new() {
+		let data = MouseData::new(10, -20, MouseButtons::new().set_left_pressed(), 3);
+		assert_eq!(data.x, 10);
+		assert_eq!(data.y, -20);
+		assert!(data.buttons.is_left_pressed());
+		assert_eq!(data.wheel, 3);
+		// The wheel fits in what used to be padding
+		assert_eq!(core::mem::size_of::<MouseData>(), 6);
+		assert_eq!(core::mem::align_of::<MouseData>(), 2);
+	}
+
+	#[test]
+	fn gamepad_buttons() {
+		let buttons = GamepadButtons::new().set_a_pressed().set_start_pressed();
+		assert!(buttons.is_a_pressed());
+		assert!(buttons.is_start_pressed());
+		assert!(!buttons.is_b_pressed());
+		assert!(!buttons.is_select_pressed());
+		let buttons = buttons.set_left_pressed().set_r_pressed();
+		assert!(buttons.is_left_pressed());
+		assert!(buttons.is_r_pressed());
+		assert!(!buttons.is_right_pressed());
+		assert!(!buttons.is_l_pressed());
+		assert_eq!(GamepadButtons::default(), GamepadButtons::new());
+	}
+
+	#[test]
+	fn modifiers() {
+		let modifiers = Modifiers::new()
+			.set_right_shift_pressed()
+			.set_left_alt_pressed()
+			.set_num_lock_on();
+		assert!(modifiers.is_right_shift_pressed());
+		assert!(!modifiers.is_left_shift_pressed());
+		assert!(modifiers.is_shift_pressed());
+		assert!(modifiers.is_alt_pressed());
+		assert!(!modifiers.is_ctrl_pressed());
+		assert!(!modifiers.is_gui_pressed());
+		assert!(modifiers.is_num_lock_on());
+		assert!(!modifiers.is_caps_lock_on());
+		assert!(!modifiers.is_scroll_lock_on());
+		assert_eq!(Modifiers::default(), Modifiers::new());
+	}
+
+	#[test]
+	fn gamepad_data_new() {
+		let buttons = GamepadButtons::new().set_x_pressed();
+		let data = GamepadData::new(1, buttons, (-128, 5), (127, -1));
+		assert_eq!(data.port, 1);
+		assert_eq!(data.buttons, buttons);
+		assert_eq!(data.left_stick(), (-128, 5));
+		assert_eq!(data.right_stick(), (127, -1));
+	}
+
+	#[test]
+	fn pointer_data_new() {
+		let data = PointerData::new(0x8000, 0xFFFF, 0, MouseButtons::new().set_left_pressed());
+		assert_eq!(data.x, 0x8000);
+		assert_eq!(data.y, 0xFFFF);
+		assert_eq!(data.pressure, 0);
+		assert!(data.buttons.is_left_pressed());
+		assert!(!data.is_touching());
+		assert!(PointerData::new(0, 0, 1, MouseButtons::new()).is_touching());
+	}
+
+	#[test]
+	fn pointer_data_position() {
+		let centre = PointerData::new(0x8000, 0x8000, 0, MouseButtons::new());
+		assert_eq!(centre.position_in(640, 480), (320, 240));
+		let top_left = PointerData::new(0, 0, 0, MouseButtons::new());
+		assert_eq!(top_left.position_in(640, 480), (0, 0));
+		let bottom_right = PointerData::new(0xFFFF, 0xFFFF, 0, MouseButtons::new());
+		assert_eq!(bottom_right.position_in(640, 480), (639, 479));
+		assert_eq!(bottom_right.position_in(u16::MAX, u16::MAX), (65534, 65534));
+	}
+
+	#[test]
+	fn encode_absolute_pointer() {
+		let event = HidEvent::AbsolutePointer(PointerData::new(
+			0x1234,
+			0xABCD,
+			0x00FF,
+			MouseButtons::new().set_right_pressed(),
+		));
+		let mut buffer = [0u8; HidEvent::MAX_ENCODED_LEN];
+		let len = event.encode(&mut buffer).unwrap();
+		assert_eq!(
+			&buffer[0..len],
+			&[6, 0x34, 0x12, 0xCD, 0xAB, 0xFF, 0x00, 0x04]
+		);
+		assert_eq!(HidEvent::decode(&buffer[0..len]), Some((event, len)));
+	}
+
+	#[test]
+	fn encode_raw_scancode() {
+		let event = HidEvent::RawScancode {
+			code: 0xE01F,
+			make: false,
+		};
+		let mut buffer = [0u8; HidEvent::MAX_ENCODED_LEN];
+		let len = event.encode(&mut buffer).unwrap();
+		assert_eq!(&buffer[0..len], &[5, 0x1F, 0xE0, 0]);
+		assert_eq!(HidEvent::decode(&buffer[0..len]), Some((event, len)));
+		assert_eq!(HidEvent::decode(&[5, 0x1F, 0xE0, 2]), None);
+	}
+
+	#[test]
+	fn encode_gamepad() {
+		let buttons = GamepadButtons::new().set_b_pressed().set_down_pressed();
+		let event = HidEvent::GamepadInput(GamepadData::new(2, buttons, (-1, 64), (0, -128)));
+		let mut buffer = [0u8; HidEvent::MAX_ENCODED_LEN];
+		let len = event.encode(&mut buffer).unwrap();
+		assert_eq!(&buffer[0..len], &[4, 2, 0x02, 0x02, 0xFF, 0x40, 0x00, 0x80]);
+		assert_eq!(HidEvent::decode(&buffer[0..len]), Some((event, len)));
+	}
+
+	#[test]
+	fn key_codes() {
+		// Each discriminant from zero upwards must be listed exactly once, so
+		// a key code `pc-keyboard` has added shows up as a gap.
+		let mut seen = [false; 256];
+		for code in KEY_CODES {
+			let value = *code as usize;
+			assert!(!seen[value], "{:?} is listed twice", code);
+			seen[value] = true;
+		}
+		assert!(seen[0..KEY_CODES.len()].iter().all(|seen| *seen));
+		// The encoding is fixed, whatever order `pc-keyboard` uses
+		for (code, value) in [
+			(KeyCode::Escape, 0),
+			(KeyCode::Key1, 18),
+			(KeyCode::A, 61),
+			(KeyCode::Return, 72),
+			(KeyCode::Spacebar, 96),
+			(KeyCode::RAlt2, 123),
+		] {
+			assert_eq!(key_code_to_u8(code), Some(value));
+			assert_eq!(key_code_from_u8(value), Some(code));
+		}
+		let mut buffer = [0u8; HidEvent::MAX_ENCODED_LEN];
+		for code in KEY_CODES {
+			let event = HidEvent::KeyRelease(*code);
+			let len = event.encode(&mut buffer).unwrap();
+			assert_eq!(HidEvent::decode(&buffer[0..len]), Some((event, len)));
+		}
+		assert_eq!(HidEvent::decode(&[HidEvent::TAG_KEY_PRESS, 0xFF]), None);
+	}
+
+	#[test]
+	fn encode_key_press() {
+		let event = HidEvent::KeyPress(KeyCode::Spacebar);
+		let mut buffer = [0u8; HidEvent::MAX_ENCODED_LEN];
+		let len = event.encode(&mut buffer).unwrap();
+		assert_eq!(len, 2);
+		assert_eq!(buffer[0], 1);
+		assert_eq!(buffer[1], 96);
+		assert_eq!(HidEvent::decode(&buffer[0..len]), Some((event, 2)));
+	}
+
+	#[test]
+	fn encode_mouse_move() {
+		let event = HidEvent::MouseInput(MouseData::new(
+			-5,
+			-300,
+			MouseButtons::new().set_right_pressed(),
+			-2,
+		));
+		let mut buffer = [0u8; HidEvent::MAX_ENCODED_LEN];
+		let len = event.encode(&mut buffer).unwrap();
+		assert_eq!(&buffer[0..len], &[3, 0xFB, 0xFF, 0xD4, 0xFE, 0x04, 0xFE]);
+		assert_eq!(HidEvent::decode(&buffer[0..len]), Some((event, len)));
+	}
+
+	#[test]
+	fn encode_truncated() {
+		let event = HidEvent::MouseInput(MouseData::new(1, 2, MouseButtons::new(), 0));
+		let mut buffer = [0u8; HidEvent::MAX_ENCODED_LEN];
+		assert_eq!(event.encode(&mut buffer[0..6]), None);
+		let len = event.encode(&mut buffer).unwrap();
+		assert_eq!(HidEvent::decode(&buffer[0..len - 1]), None);
+		assert_eq!(HidEvent::decode(&[]), None);
+		assert_eq!(HidEvent::decode(&[1, 0xFF]), None);
+	}
+}
+
 // ============================================================================
 // End of File
 // ============================================================================
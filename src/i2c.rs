@@ -43,13 +43,34 @@ pub struct BusInfo {
 	/// Some human-readable name for this I²C device (e.g. `I²C0` or
 	/// `DDC0`)
 	pub name: crate::FfiString<'static>,
+	/// The fastest clock speed this bus can run at, in Hz.
+	///
+	/// See `Api::i2c_bus_set_speed`.
+	pub max_speed_hz: u32,
+	/// Can this bus talk to devices with 10-bit addresses?
+	pub supports_10bit: bool,
+	/// Does this bus wait for devices which hold the clock line low
+	/// (*clock stretching*)?
+	pub supports_clock_stretching: bool,
 }
 
 // ============================================================================
 // Impls
 // ============================================================================
 
-// None
+#[cfg(feature = "defmt")]
+impl defmt::Format for BusInfo {
+	fn format(&self, f: defmt::Formatter) {
+		defmt::write!(
+			f,
+			"BusInfo {{ name: {=str}, max_speed_hz: {}, supports_10bit: {}, supports_clock_stretching: {} }}",
+			self.name.as_str(),
+			self.max_speed_hz,
+			self.supports_10bit,
+			self.supports_clock_stretching
+		)
+	}
+}
 
 // ============================================================================
 // End of File
@@ -22,6 +22,9 @@
 #![no_std]
 #![deny(missing_docs)]
 
+#[cfg(feature = "std")]
+extern crate std;
+
 // ============================================================================
 // Imports
 // ============================================================================
@@ -35,6 +38,10 @@ pub mod serial;
 pub mod types;
 pub mod version;
 pub mod video;
+pub mod wrapper;
+
+#[cfg(test)]
+mod stub;
 
 pub use types::*;
 pub use version::Version;
@@ -46,7 +53,23 @@ pub use neotron_ffi::{FfiBuffer, FfiByteSlice, FfiOption, FfiResult, FfiString};
 // ============================================================================
 
 /// BIOS API semantic version for the API defined in this crate.
-pub const API_VERSION: Version = Version::new(0, 6, 1);
+pub const API_VERSION: Version = Version::new(0, 7, 0);
+
+/// The initial value of a 32-bit FNV-1a hash.
+const FNV_OFFSET_BASIS: u32 = 0x811C_9DC5;
+
+/// The multiplier used by a 32-bit FNV-1a hash.
+const FNV_PRIME: u32 = 0x0100_0193;
+
+/// The longest system name, in bytes, that a BIOS must be able to store.
+///
+/// See [`Api::system_name_set`].
+pub const SYSTEM_NAME_MAX_LEN: usize = 31;
+
+/// The longest unique machine ID, in bytes, that a BIOS can report.
+///
+/// See [`Api::machine_get_unique_id`].
+pub const MACHINE_ID_MAX_LEN: usize = 16;
 
 // ============================================================================
 // Macros
@@ -74,6 +97,8 @@ macro_rules! make_ffi_enum {
 		#[doc = stringify!($ffi_enum_name)]
 		/// ] for transport across an FFI boundary.
 		#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+		#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+		#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 		#[non_exhaustive]
 		#[repr(u8)]
 		pub enum $enum_name {
@@ -99,6 +124,8 @@ macro_rules! make_ffi_enum {
 		/// ]
 		#[repr(transparent)]
 		#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+		#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+		#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 		pub struct $ffi_enum_name(pub u8);
 
 		impl $ffi_enum_name {
@@ -143,6 +170,42 @@ macro_rules! make_ffi_enum {
 	}
 }
 
+/// Builds a table of `(name, byte offset)` for the given fields of [`Api`].
+macro_rules! api_field_offsets {
+	($($field:ident),+ $(,)?) => {
+		&[$((stringify!($field), ::core::mem::offset_of!(Api, $field))),+]
+	};
+}
+
+/// Builds a table of `(name, size, alignment)` for the given types.
+macro_rules! abi_layout {
+	($($ty:ty),+ $(,)?) => {
+		&[$((
+			stringify!($ty),
+			::core::mem::size_of::<$ty>(),
+			::core::mem::align_of::<$ty>(),
+		)),+]
+	};
+}
+
+/// Fails the build if any of the given types do not have the given size and
+/// alignment, in bytes.
+#[cfg(target_pointer_width = "32")]
+macro_rules! assert_abi_layout {
+	($($ty:ty => ($size:expr, $align:expr)),+ $(,)?) => {
+		$(
+			const _: () = assert!(
+				::core::mem::size_of::<$ty>() == $size,
+				concat!("size of ", stringify!($ty), " has changed")
+			);
+			const _: () = assert!(
+				::core::mem::align_of::<$ty>() == $align,
+				concat!("alignment of ", stringify!($ty), " has changed")
+			);
+		)+
+	};
+}
+
 // ============================================================================
 // Types
 // ============================================================================
@@ -166,6 +229,13 @@ pub struct Api {
 	///
 	/// You need this value to determine which of the following API calls are
 	/// valid in this particular version.
+	///
+	/// At start-up, the OS should first check this against [`API_VERSION`]
+	/// with [`Version::is_compatible_with`]. If they are compatible, it
+	/// should then check that `api_layout_hash` returns the same value as
+	/// its own [`Api::layout_hash`]. If either check fails, the BIOS was
+	/// built against an incompatible version of this crate and no other
+	/// function in this structure can safely be called.
 	pub api_version_get: extern "C" fn() -> Version,
 	/// Returns a pointer to a static string slice.
 	///
@@ -176,6 +246,16 @@ pub struct Api {
 	/// a Rust string. It is unspecified as to whether the string is located
 	/// in Flash ROM or RAM (but it's likely to be Flash ROM).
 	pub bios_version_get: extern "C" fn() -> FfiString<'static>,
+	/// Get the layout hash of the `Api` structure this BIOS was built with.
+	///
+	/// This is the value of [`Api::layout_hash`] in the BIOS's copy of this
+	/// crate. See `api_version_get` for how the OS should use it.
+	///
+	/// This field must stay third, straight after `bios_version_get`. The
+	/// OS calls it before it knows whether the rest of this structure is laid
+	/// out the way it expects, so it must be at the same offset in every
+	/// version of this crate.
+	pub api_layout_hash: extern "C" fn() -> u32,
 
 	// ========================================================================
 	// Serial Port Support
@@ -195,6 +275,10 @@ pub struct Api {
 	pub serial_get_info: extern "C" fn(device_id: u8) -> crate::FfiOption<serial::DeviceInfo>,
 	/// Set the options for a given serial device. An error is returned if the
 	/// options are invalid for that serial device.
+	///
+	/// Not every device supports every option (e.g. 5 data bits, or 1.5 stop
+	/// bits). If the device cannot use the given options, it returns
+	/// `Error::UnsupportedConfiguration`.
 	pub serial_configure:
 		extern "C" fn(device_id: u8, config: serial::Config) -> crate::ApiResult<()>,
 	/// Write bytes to a serial port. There is no sense of 'opening' or
@@ -202,6 +286,10 @@ pub struct Api {
 	/// value is `Ok(n)`, the value `n` may be less than the size of the given
 	/// buffer. If so, that means not all of the data could be transmitted -
 	/// only the first `n` bytes were.
+	///
+	/// A `timeout` of `None` uses the BIOS's default timeout for the device,
+	/// which is always finite. A `timeout` of `Some(Timeout::UNBOUNDED)`
+	/// means this call never times out.
 	pub serial_write: extern "C" fn(
 		device_id: u8,
 		data: FfiByteSlice,
@@ -213,11 +301,112 @@ pub struct Api {
 	/// the given buffer. If so, that means not all of the requested data
 	/// could be received - only the first `n` bytes were (and hence only the
 	/// first `n` bytes of the given buffer now contain data).
+	///
+	/// A `timeout` of `None` uses the BIOS's default timeout for the device,
+	/// which is always finite. A `timeout` of `Some(Timeout::UNBOUNDED)`
+	/// means this call never times out.
 	pub serial_read: extern "C" fn(
 		device_id: u8,
 		data: FfiBuffer,
 		timeout: crate::FfiOption<Timeout>,
 	) -> crate::ApiResult<usize>,
+	/// Write bytes to a serial port, then read the reply.
+	///
+	/// This is for half-duplex links like RS-485 (e.g. Modbus RTU), where
+	/// the gap between sending a request and listening for the reply is
+	/// tight. The BIOS writes all of `tx`, switches the line driver around
+	/// (if the device has one), waits for the `turnaround` period, and then
+	/// reads into `rx` until the line goes idle, `rx` is full, or the
+	/// `timeout` expires. If the return value is `Ok(n)`, only the first `n`
+	/// bytes of `rx` contain data.
+	///
+	/// The `turnaround` is usually a few character times, which at higher
+	/// baud rates is well under a millisecond - so it is given as an
+	/// [`FfiDuration`], and the BIOS should wait at least that long.
+	///
+	/// For devices which are not RS-485, this behaves like a `serial_write`
+	/// followed immediately by a `serial_read`.
+	///
+	/// A `timeout` of `None` uses the BIOS's default timeout for the device,
+	/// which is always finite. A `timeout` of `Some(Timeout::UNBOUNDED)`
+	/// means this call never times out.
+	pub serial_transaction: extern "C" fn(
+		device_id: u8,
+		tx: FfiByteSlice,
+		rx: FfiBuffer,
+		turnaround: FfiDuration,
+		timeout: crate::FfiOption<Timeout>,
+	) -> crate::ApiResult<usize>,
+	/// Read bytes from a serial port, along with the status of each byte.
+	///
+	/// This works like `serial_read`, but for every byte written to `data`,
+	/// a [`serial::ByteStatus`] is written to the same position in `status`,
+	/// noting any parity error, framing error or break. This is needed for
+	/// protocols which use the parity bit as a 9th address bit, or which must
+	/// discard corrupted bytes.
+	///
+	/// A BIOS which cannot report per-byte status fills `status` with zeros.
+	/// The two buffers must be the same length, otherwise
+	/// `Error::UnsupportedConfiguration` is returned.
+	///
+	/// A `timeout` of `None` uses the BIOS's default timeout for the device,
+	/// which is always finite. A `timeout` of `Some(Timeout::UNBOUNDED)`
+	/// means this call never times out.
+	pub serial_read_tagged: extern "C" fn(
+		device_id: u8,
+		data: FfiBuffer,
+		status: FfiBuffer,
+		timeout: crate::FfiOption<Timeout>,
+	) -> crate::ApiResult<usize>,
+	/// Wait until all the data written to a serial port has been sent.
+	///
+	/// A successful `serial_write` only means that the BIOS has accepted the
+	/// data. This function blocks until every byte has been clocked out of
+	/// the hardware (i.e. both the transmit FIFO and the shift register are
+	/// empty), which is useful before a controlled shutdown or reset.
+	///
+	/// Returns `Error::Timeout` if the data has not all been sent before the
+	/// `timeout` expires, or `Error::DeviceError` if the hardware fails.
+	///
+	/// A `timeout` of `None` uses the BIOS's default timeout for the device,
+	/// which is always finite. A `timeout` of `Some(Timeout::UNBOUNDED)`
+	/// means this call never times out.
+	pub serial_flush:
+		extern "C" fn(device_id: u8, timeout: crate::FfiOption<Timeout>) -> crate::ApiResult<()>,
+	/// Set the modem control lines on a serial port.
+	///
+	/// Lines which are not marked as asserted in `lines` are de-asserted. If
+	/// the port uses hardware handshaking, the BIOS may still control RTS
+	/// itself.
+	///
+	/// A serial device without these lines returns `Error::Unimplemented`.
+	pub serial_set_control_lines:
+		extern "C" fn(device_id: u8, lines: serial::ControlLines) -> crate::ApiResult<()>,
+	/// Get the current state of the modem status lines on a serial port.
+	///
+	/// A serial device without these lines returns `Error::Unimplemented`.
+	pub serial_get_status_lines:
+		extern "C" fn(device_id: u8) -> crate::ApiResult<serial::StatusLines>,
+	/// Send a break condition on a serial port.
+	///
+	/// The BIOS first waits for any data already written to the port to be
+	/// sent (as per `serial_flush`), and then holds the transmit line in the
+	/// *spacing* state for `duration_ms` milliseconds. This is used to reset
+	/// some microcontrollers, or to enter their bootloaders.
+	///
+	/// A serial device which cannot produce a timed break returns
+	/// `Error::Unimplemented`.
+	pub serial_send_break: extern "C" fn(device_id: u8, duration_ms: u16) -> crate::ApiResult<()>,
+	/// Get how many bytes have been received on a serial port.
+	///
+	/// This many bytes can be read right now using `serial_read` without
+	/// blocking.
+	pub serial_get_rx_count: extern "C" fn(device_id: u8) -> crate::ApiResult<usize>,
+	/// Get how much transmit buffer space a serial port has.
+	///
+	/// This many bytes can be sent right now using `serial_write` without
+	/// blocking.
+	pub serial_get_tx_space: extern "C" fn(device_id: u8) -> crate::ApiResult<usize>,
 
 	// ========================================================================
 	// Time Support
@@ -252,6 +441,34 @@ pub struct Api {
 	pub time_ticks_get: extern "C" fn() -> Ticks,
 	/// Report the system tick rate, in ticks-per-second.
 	pub time_ticks_per_second: extern "C" fn() -> Ticks,
+	/// Wait for at least the given number of milliseconds.
+	///
+	/// This uses the BIOS's own timing source, so it saves every driver from
+	/// having to spin on `time_ticks_get`. It may wait longer than asked
+	/// for, but never less. An emulator may put the thread to sleep; real
+	/// hardware will busy-wait or use a timer.
+	pub delay_ms: extern "C" fn(milliseconds: u32),
+	/// Wait for at least the given number of microseconds.
+	///
+	/// As `delay_ms`, but for the short delays that hardware often needs
+	/// (e.g. settling times during peripheral initialisation).
+	pub delay_us: extern "C" fn(microseconds: u32),
+	/// Set the real-time clock alarm.
+	///
+	/// When the wall time (see `time_clock_get`) reaches the given `time`,
+	/// the system is switched on if it was off. If it is already running, an
+	/// interrupt is raised instead, which the OS will see in
+	/// `bus_interrupt_status` (which bit is BIOS-specific). Setting an alarm
+	/// replaces any previous one.
+	///
+	/// The alarm only has a resolution of whole seconds - the `nsecs` field
+	/// of `time` is ignored.
+	///
+	/// A system without an alarm-capable real-time clock returns
+	/// `Error::Unimplemented`.
+	pub time_alarm_set: extern "C" fn(time: Time) -> crate::ApiResult<()>,
+	/// Cancel any alarm set with `time_alarm_set`.
+	pub time_alarm_clear: extern "C" fn() -> crate::ApiResult<()>,
 
 	// ========================================================================
 	// Persistent Configuration Support
@@ -261,11 +478,50 @@ pub struct Api {
 	/// Configuration data is, to the BIOS, just a block of bytes of a given
 	/// length. How it stores them is up to the BIOS - it could be EEPROM, or
 	/// battery-backed SRAM.
+	///
+	/// As much of the block as will fit is copied into `buffer`, and the full
+	/// length of the block is returned. If that is larger than the buffer,
+	/// the block has been truncated, and the OS should try again with a
+	/// buffer of at least that size (or of `configuration_get_max_len`
+	/// bytes).
 	pub configuration_get: extern "C" fn(buffer: FfiBuffer) -> crate::ApiResult<usize>,
 	/// Set the configuration data block.
 	///
 	/// See `configuration_get`.
 	pub configuration_set: extern "C" fn(buffer: FfiByteSlice) -> crate::ApiResult<()>,
+	/// Get the size of the configuration data store, in bytes.
+	///
+	/// A buffer this big is always large enough for `configuration_get`, and
+	/// `configuration_set` will reject any block larger than this.
+	pub configuration_get_max_len: extern "C" fn() -> usize,
+	/// Get the system name.
+	///
+	/// This is a short, human-readable, name for this computer (like a
+	/// hostname), stored in persistent storage separately from the
+	/// configuration data block, so it survives reboots and firmware updates.
+	///
+	/// The name is copied into `buffer` and the length of the name is
+	/// returned. A name that has never been set has a length of zero.
+	pub system_name_get: extern "C" fn(buffer: FfiBuffer) -> crate::ApiResult<usize>,
+	/// Set the system name.
+	///
+	/// See `system_name_get`. The name must be ASCII and no longer than
+	/// [`SYSTEM_NAME_MAX_LEN`] bytes, otherwise
+	/// `Error::UnsupportedConfiguration` is returned.
+	pub system_name_set: extern "C" fn(name: FfiByteSlice) -> crate::ApiResult<()>,
+	/// Get the factory-programmed unique ID of this particular board.
+	///
+	/// Unlike `bios_version_get`, which describes the firmware, this
+	/// identifies the physical hardware - for example, the RP2040 has a
+	/// 64-bit unique ID in its flash chip. The ID is an opaque string of
+	/// bytes, and its length depends on the BIOS, but it is never longer
+	/// than [`MACHINE_ID_MAX_LEN`] bytes.
+	///
+	/// As much of the ID as will fit is copied into `out`, and the full
+	/// length of the ID is returned. If that is larger than `out`, the ID has
+	/// been truncated - as with `configuration_get`. A system with no unique
+	/// ID returns `Error::Unimplemented`.
+	pub machine_get_unique_id: extern "C" fn(out: FfiBuffer) -> crate::ApiResult<usize>,
 
 	// ========================================================================
 	// Video Output Support
@@ -338,6 +594,9 @@ pub struct Api {
 	/// You can also use this for a crude `16.7 ms` delay but note that
 	/// some video modes run at `70 Hz` and so this would then give you a
 	/// `14.3ms` second delay.
+	///
+	/// This function spins until the line is reached. To do something else
+	/// whilst waiting, see `video_set_line_interrupt`.
 	pub video_wait_for_line: extern "C" fn(line: u16),
 	/// Get an entry from the colour palette.
 	///
@@ -374,6 +633,197 @@ pub struct Api {
 	///
 	pub video_set_whole_palette:
 		unsafe extern "C" fn(start: *const video::RGBColour, length: usize),
+	/// Rotate a range of entries in the colour palette.
+	///
+	/// The `count` entries starting at `start` are rotated by `step`
+	/// positions, wrapping around within that range. With a positive `step`,
+	/// the colour at `start` moves to `start + step`. Calling this once per
+	/// frame gives classic colour-cycling effects (waterfalls, fire, etc)
+	/// without re-uploading palette entries one at a time.
+	///
+	/// A range which extends beyond the palette of the current video mode is
+	/// clamped to fit. In video modes without a palette (`Chunky16` and
+	/// `Chunky32`), this returns `Error::UnsupportedConfiguration`.
+	pub video_cycle_palette: extern "C" fn(start: u8, count: u8, step: i8) -> crate::ApiResult<()>,
+	/// Get how many entries the colour palette has in the current video
+	/// mode.
+	///
+	/// For example, a `Chunky4` mode has 16 palette entries and a `Chunky8`
+	/// mode has 256. Direct-colour modes (`Chunky16` and `Chunky32`) don't
+	/// use the palette, and so return `0`. Calls to `video_set_palette`
+	/// with an index at or beyond this value are ignored.
+	pub video_get_palette_size: extern "C" fn() -> u16,
+	/// Show a test pattern instead of the framebuffer.
+	///
+	/// Whilst a pattern is active, the BIOS generates it on-the-fly in place of
+	/// the normal framebuffer scan-out, in a way suitable for the current
+	/// video mode's format (including text modes). The contents of the
+	/// framebuffer are not modified. Select `TestPattern::Off` to restore
+	/// normal output.
+	///
+	/// This is useful for display bring-up, or for a "display settings"
+	/// screen where the user adjusts an analog monitor.
+	///
+	/// A BIOS may only support some of the patterns, and will return
+	/// `Error::UnsupportedConfiguration` for the others.
+	pub video_show_test_pattern:
+		extern "C" fn(pattern: video::FfiTestPattern) -> crate::ApiResult<()>,
+	/// Get a video mode supported by this BIOS.
+	///
+	/// The OS should call this with `index` values counting up from `0` until
+	/// it gets `None`, which marks the end of the list. Every mode returned
+	/// will also be accepted by `video_is_valid_mode`.
+	///
+	/// The order of the list is defined by the BIOS, but it will not change
+	/// until the system is restarted.
+	pub video_get_supported_mode: extern "C" fn(index: u8) -> crate::FfiOption<video::Mode>,
+	/// Move the hardware text cursor.
+	///
+	/// The `x` and `y` values are the column and row of a character cell,
+	/// counting from `(0, 0)` at the top left of the screen. The cursor is
+	/// drawn by the video hardware on top of the framebuffer, so the contents
+	/// of the framebuffer are never changed.
+	///
+	/// Returns `Error::UnsupportedConfiguration` if the current mode is not a
+	/// text mode, or if the position is off the screen.
+	pub video_set_cursor: extern "C" fn(x: u16, y: u16, visible: bool) -> crate::ApiResult<()>,
+	/// Set the shape of the hardware text cursor.
+	///
+	/// See `video_set_cursor`. A BIOS may not support every style, and will
+	/// return `Error::UnsupportedConfiguration` for the others.
+	pub video_set_cursor_style: extern "C" fn(style: video::FfiCursorStyle) -> crate::ApiResult<()>,
+	/// Replace the font used in text modes.
+	///
+	/// The `font_data` holds 256 glyphs, one after the other, each of which
+	/// is `glyph_height` bytes long (one byte per row, with the left-most
+	/// pixel in the top bit). It must therefore be `256 * glyph_height`
+	/// bytes long. The BIOS copies the glyphs into its own memory before
+	/// returning.
+	///
+	/// The `glyph_height` must match the current text mode (`8` for
+	/// `Text8x8`, `16` for `Text8x16`), otherwise
+	/// `Error::UnsupportedConfiguration` is returned.
+	///
+	/// # Safety
+	///
+	/// The `font_data` must point to valid memory of the given length.
+	pub video_set_font:
+		unsafe extern "C" fn(font_data: FfiByteSlice, glyph_height: u8) -> crate::ApiResult<()>,
+	/// Go back to using the BIOS's built-in text mode font.
+	///
+	/// See `video_set_font`.
+	pub video_reset_font: extern "C" fn() -> crate::ApiResult<()>,
+	/// Register an extra framebuffer, for double-buffering.
+	///
+	/// Returns a handle which can be passed to `video_flip_framebuffer`. The
+	/// framebuffer is laid out according to the current video mode. All
+	/// registered framebuffers are forgotten when `video_set_mode` is
+	/// called.
+	///
+	/// A BIOS which can only display one framebuffer will return
+	/// `Error::Unimplemented`.
+	///
+	/// # Safety
+	///
+	/// The `start_address` must be the start of a 32-bit aligned block which
+	/// is at least [`frame_size_bytes()`](video::Mode::frame_size_bytes)
+	/// bytes in length, and it must remain valid until the next call to
+	/// `video_set_mode`.
+	pub video_register_framebuffer:
+		unsafe extern "C" fn(start_address: *const u8) -> crate::ApiResult<u8>,
+	/// Display a framebuffer previously registered with
+	/// `video_register_framebuffer`.
+	///
+	/// The switch happens during the next vertical blanking interval, so no
+	/// frame is ever drawn partly from one framebuffer and partly from
+	/// another. This function returns immediately - use
+	/// `video_wait_for_line` if you need to know when the switch has
+	/// happened.
+	///
+	/// Returns `Error::InvalidDevice` if the handle is not known, or
+	/// `Error::Unimplemented` if the BIOS does not support double-buffering.
+	pub video_flip_framebuffer: extern "C" fn(handle: u8) -> crate::ApiResult<()>,
+	/// Get all the entries in the colour palette.
+	///
+	/// This is the counterpart to `video_set_whole_palette`. The colours are
+	/// copied into the array at `out`, starting with palette index `0`.
+	///
+	/// Writes as many entries as the current video mode has in its palette,
+	/// or `max_len` entries, whichever is smaller, and returns the number of
+	/// entries written.
+	///
+	/// # Safety
+	///
+	/// The value `out` must point to an array of `RGBColour` of length
+	/// `max_len`.
+	pub video_get_whole_palette:
+		unsafe extern "C" fn(out: *mut video::RGBColour, max_len: usize) -> usize,
+	/// Are blinking elements currently visible?
+	///
+	/// Text with the blink attribute set, and the hardware text cursor, are
+	/// shown while this returns `true` and hidden while it returns `false`.
+	/// This lets the OS blink anything it draws itself in step with them.
+	///
+	/// The phase is driven by a timer inside the BIOS and changes twice a
+	/// second, giving a nominal blink rate of 1 Hz.
+	pub video_get_blink_phase: extern "C" fn() -> bool,
+	/// Fill a rectangle of the framebuffer with a single value.
+	///
+	/// In text modes, `x` and `w` are measured in characters and `y` and `h`
+	/// in rows of text, and `value` is a [`video::GlyphAttr`] in the bottom
+	/// 16 bits. In all other modes, they are measured in pixels and `value`
+	/// is a pixel value in the current format (e.g. a palette index, or a
+	/// `Chunky16` colour), in the bottom bits.
+	///
+	/// The rectangle is checked against the current video mode, and if any
+	/// of it falls outside the screen, `Error::UnsupportedConfiguration` is
+	/// returned and nothing is drawn.
+	///
+	/// The BIOS may use a DMA engine to do this much faster than the OS
+	/// could with a CPU loop, but a BIOS that cannot do this any faster
+	/// than the OS can may return `Error::Unimplemented`.
+	pub video_fill_rect:
+		extern "C" fn(x: u16, y: u16, w: u16, h: u16, value: u32) -> crate::ApiResult<()>,
+	/// Copy a rectangle of data into the framebuffer.
+	///
+	/// The source data is `h` lines, the start of each being `src_stride`
+	/// bytes after the start of the previous one, and each line holding `w`
+	/// characters or pixels in the same format as the framebuffer. It is
+	/// copied to position (`x`, `y`) on the screen. Units and bounds-checking
+	/// are as for `video_fill_rect`.
+	///
+	/// The source may be inside the framebuffer itself (e.g. for scrolling),
+	/// and the copy will still be correct if the two areas overlap.
+	///
+	/// A BIOS that cannot do this any faster than the OS can may return
+	/// `Error::Unimplemented`.
+	///
+	/// # Safety
+	///
+	/// The value `src` must point to `h` lines of valid data, spaced
+	/// `src_stride` bytes apart.
+	pub video_blit: unsafe extern "C" fn(
+		src: *const u8,
+		src_stride: usize,
+		x: u16,
+		y: u16,
+		w: u16,
+		h: u16,
+	) -> crate::ApiResult<()>,
+	/// Raise an interrupt every time the given video scan-line is reached.
+	///
+	/// Unlike `video_wait_for_line`, this doesn't keep the CPU busy. The OS
+	/// can sit in `power_idle` and be woken when the line is reached, and
+	/// will then see the interrupt in `bus_interrupt_status` (which bit is
+	/// BIOS-specific). Only one line can be set at a time - setting a new
+	/// line replaces the old one, and passing `enabled = false` turns the
+	/// interrupt off.
+	///
+	/// This doesn't affect `video_wait_for_line`, which can still be used
+	/// whether or not a line interrupt is enabled.
+	///
+	/// A BIOS which cannot do this returns `Error::Unimplemented`.
+	pub video_set_line_interrupt: extern "C" fn(line: u16, enabled: bool) -> crate::ApiResult<()>,
 
 	// ========================================================================
 	// Memory Region Support
@@ -405,6 +855,53 @@ pub struct Api {
 	pub hid_get_event: extern "C" fn() -> crate::ApiResult<crate::FfiOption<hid::HidEvent>>,
 	/// Control the keyboard LEDs.
 	pub hid_set_leds: extern "C" fn(leds: hid::KeyboardLeds) -> crate::ApiResult<()>,
+	/// Look at the next available HID event, if any, without removing it.
+	///
+	/// A call to `hid_get_event` after this will return the same event (unless
+	/// `hid_peek_event` returned `Ok(None)`). The BIOS must ensure that no
+	/// event can be added to, or lost from, the front of the queue between the
+	/// two calls, even if new events arrive in an interrupt.
+	///
+	/// This function doesn't block. A BIOS which cannot look at an event
+	/// without removing it will return `Error::Unimplemented`.
+	pub hid_peek_event: extern "C" fn() -> crate::ApiResult<crate::FfiOption<hid::HidEvent>>,
+	/// Get which modifier keys are currently held down, and which lock keys
+	/// are active.
+	///
+	/// The BIOS keeps track of this as it processes key events, so the answer
+	/// is correct even if the OS has not yet collected all the events with
+	/// `hid_get_event`.
+	pub hid_get_modifiers: extern "C" fn() -> hid::Modifiers,
+	/// Get information about the Human Interface Devices in the system.
+	///
+	/// HID Devices are numbered from `0`. The OS should call this with
+	/// increasing `device_id` values until it gets `None`. A device may be
+	/// listed even when it is not plugged in (for example, a USB port), in
+	/// which case `connected` will be `false`.
+	pub hid_get_device_info: extern "C" fn(device_id: u8) -> crate::FfiOption<hid::DeviceInfo>,
+	/// Configure keyboard auto-repeat (also known as *typematic* repeat).
+	///
+	/// When a key has been held down for `delay_ms` milliseconds, the BIOS
+	/// starts generating extra `HidEvent::KeyPress` events for it, one every
+	/// `rate_ms` milliseconds, just as a PC keyboard controller would. Only
+	/// the most recently pressed key repeats, and the repeating stops as
+	/// soon as that key is released (which still produces the usual
+	/// `HidEvent::KeyRelease`) or another key is pressed.
+	///
+	/// A `delay_ms` of `0` turns off auto-repeat in the BIOS, leaving the OS
+	/// to implement it if it wants to.
+	pub hid_set_repeat_config: extern "C" fn(delay_ms: u16, rate_ms: u16) -> crate::ApiResult<()>,
+	/// Turn raw keyboard scancodes on or off.
+	///
+	/// When raw mode is on, the BIOS sends a `HidEvent::RawScancode` for
+	/// every key press and release, which lets a program tell apart keys
+	/// that decode to the same `KeyCode`, or see keys which don't decode to
+	/// anything at all. The BIOS may stop sending `HidEvent::KeyPress` and
+	/// `HidEvent::KeyRelease` while raw mode is on, so that each key press
+	/// isn't reported twice - the OS should not rely on getting both.
+	///
+	/// Raw mode is off when the system starts.
+	pub hid_set_raw_mode: extern "C" fn(enabled: bool) -> crate::ApiResult<()>,
 
 	// ========================================================================
 	// I²C Bus Support
@@ -413,6 +910,20 @@ pub struct Api {
 	///
 	/// I²C Bus 0 should be the one connected to the Neotron Bus.
 	/// I²C Bus 1 is typically the VGA DDC bus.
+	///
+	/// ```no_run
+	/// # let api = neotron_common_bios::Api::make_dummy_api().unwrap();
+	/// # use neotron_common_bios::FfiOption;
+	/// // Find the first I²C Bus which can run in Fast-mode
+	/// let mut bus_id = 0;
+	/// while let FfiOption::Some(info) = (api.i2c_bus_get_info)(bus_id) {
+	///     if info.max_speed_hz >= 400_000 {
+	///         let _ = (api.i2c_bus_set_speed)(bus_id, 400_000);
+	///         break;
+	///     }
+	///     bus_id += 1;
+	/// }
+	/// ```
 	pub i2c_bus_get_info: extern "C" fn(bus_id: u8) -> crate::FfiOption<i2c::BusInfo>,
 	/// Transact with a I²C Device on an I²C Bus
 	///
@@ -440,6 +951,48 @@ pub struct Api {
 		tx2: FfiByteSlice,
 		rx: FfiBuffer,
 	) -> crate::ApiResult<()>,
+	/// Take exclusive access to an I²C Bus.
+	///
+	/// Whilst the bus is locked, only the holder of the lock should call
+	/// `i2c_write_read` on it - so a driver can perform several transactions
+	/// (e.g. reading a set of registers) without another driver getting in
+	/// between them. As with `bus_select`, interrupt routines that need a
+	/// locked bus are blocked and must be deferred, so don't hold the lock
+	/// for longer than you need to.
+	///
+	/// Calling this on a bus that is already locked returns `Error::Busy`.
+	pub i2c_lock: extern "C" fn(bus_id: u8) -> crate::ApiResult<()>,
+	/// Release exclusive access to an I²C Bus.
+	///
+	/// See `i2c_lock`.
+	pub i2c_unlock: extern "C" fn(bus_id: u8) -> crate::ApiResult<()>,
+	/// Find out which devices are present on an I²C Bus.
+	///
+	/// The BIOS probes each 7-bit address in turn (in whatever way is safe
+	/// for its controller - typically a zero-length write), and writes the
+	/// address of each device that responds into `found`, in ascending
+	/// order. Returns the number of addresses written. If `found` fills up,
+	/// the scan stops early.
+	///
+	/// Every address may briefly see a START and STOP condition on the bus,
+	/// which some badly-behaved devices may not like. The BIOS may skip the
+	/// reserved addresses (`0x00` to `0x07` and `0x78` to `0x7F`).
+	pub i2c_bus_scan: extern "C" fn(bus_id: u8, found: FfiBuffer) -> crate::ApiResult<usize>,
+	/// Set the clock speed of an I²C Bus.
+	///
+	/// Common speeds are 100 kHz (*Standard-mode*), 400 kHz (*Fast-mode*) and
+	/// 1 MHz (*Fast-mode Plus*). The BIOS picks the fastest speed it can
+	/// achieve which is no faster than `speed_hz` (or the bus's
+	/// `max_speed_hz`, as given by `i2c_bus_get_info`), and returns that
+	/// speed.
+	///
+	/// If the bus cannot go as slowly as requested, or if the bus speed is
+	/// fixed, `Error::UnsupportedConfiguration` is returned.
+	pub i2c_bus_set_speed: extern "C" fn(bus_id: u8, speed_hz: u32) -> crate::ApiResult<u32>,
+	/// Get the current clock speed of an I²C Bus.
+	///
+	/// See `i2c_bus_set_speed`.
+	pub i2c_bus_get_speed: extern "C" fn(bus_id: u8) -> crate::ApiResult<u32>,
 
 	// ========================================================================
 	// Audio Support
@@ -453,11 +1006,13 @@ pub struct Api {
 	/// Configure the audio output.
 	///
 	/// If accepted, the audio output FIFO is flushed and the changes apply
-	/// immediately. If not accepted, an error is returned.
+	/// immediately. If not accepted, an error is returned - for example
+	/// `Error::UnsupportedConfiguration` if the hardware cannot handle the
+	/// given sample format.
 	///
 	/// It is not currently possible to enumerate all the possible sample
-	/// rates - you just have to try a variety of well know configurations to
-	/// see which ones work.
+	/// rates, but you can use `audio_output_query_config` to find out whether
+	/// a configuration would work without disturbing the current one.
 	///
 	/// Note that if your desired sample rate cannot be exactly accepted, but
 	/// is within some tolerance, this function will still succeed. Therefore
@@ -475,9 +1030,9 @@ pub struct Api {
 	/// Send audio samples to the output FIFO.
 	///
 	/// The format of the samples (little-endian, 16-bit, etc), depends on the
-	/// current output configuration. Note that the slice is in *bytes* and
-	/// there will be between *one* and *four* bytes per sample depending on
-	/// the format.
+	/// current output configuration. Note that the slice is in *bytes* - use
+	/// [`audio::SampleFormat::bytes_per_frame`] to convert to and from a
+	/// number of samples.
 	///
 	/// This function won't block, but it will return how much data was
 	/// accepted. The given samples will be copied and so the buffer is free
@@ -502,8 +1057,8 @@ pub struct Api {
 	/// immediately. If not accepted, an error is returned.
 	///
 	/// It is not currently possible to enumerate all the possible sample
-	/// rates - you just have to try a variety of well know configurations to
-	/// see which ones work.
+	/// rates, but you can use `audio_input_query_config` to find out whether
+	/// a configuration would work without disturbing the current one.
 	///
 	/// Note that if your desired sample rate cannot be exactly accepted, but
 	/// is within some tolerance, this function will still succeed. Therefore
@@ -518,9 +1073,9 @@ pub struct Api {
 	/// Get 16-bit stereo audio from the input FIFO.
 	///
 	/// The format of the samples (little-endian, 16-bit, etc), depends on the
-	/// current output configuration. Note that the slice is in *bytes* and
-	/// there will be between *one* and *four* bytes per sample depending on
-	/// the format.
+	/// current output configuration. Note that the slice is in *bytes* - use
+	/// [`audio::SampleFormat::bytes_per_frame`] to convert to and from a
+	/// number of samples.
 	///
 	/// This function won't block, but it will return how much data was
 	/// actually written to the buffer.
@@ -533,6 +1088,98 @@ pub struct Api {
 	/// How many samples in the current format can be read right now using
 	/// `audio_input_data`?
 	pub audio_input_get_count: extern "C" fn() -> crate::ApiResult<usize>,
+	/// Route the audio input directly to the audio output.
+	///
+	/// This lets the user hear their own microphone (also known as
+	/// side-tone, or monitoring), at the given level on a scale of `0` to
+	/// the `max_level` of the relevant *Loopback* Audio Mixer Channel.
+	///
+	/// The routing happens inside the BIOS (ideally in hardware), so it is
+	/// independent of, and has much lower latency than, the OS reading
+	/// samples with `audio_input_data` and writing them with
+	/// `audio_output_data`.
+	///
+	/// A BIOS without audio input, or without loopback support, returns
+	/// `Error::Unimplemented`.
+	pub audio_set_monitor: extern "C" fn(enabled: bool, level: u8) -> crate::ApiResult<()>,
+	/// Play a simple tone, such as a beep.
+	///
+	/// The BIOS generates a waveform (typically a square wave) at the given
+	/// frequency, for `duration_ms` milliseconds, mixed into the audio output.
+	/// A BIOS with more than one tone generator (e.g. several PWM beepers)
+	/// selects one using `channel` - otherwise use channel `0`. Playing a new
+	/// tone on a channel replaces any tone already playing on it.
+	///
+	/// This function returns immediately. On some systems the tone is
+	/// generated through the audio output FIFO, so while it plays, data sent
+	/// with `audio_output_data` may be delayed or not accepted.
+	pub audio_play_tone:
+		extern "C" fn(frequency_hz: u16, duration_ms: u16, channel: u8) -> crate::ApiResult<()>,
+	/// Stop any tone playing on the given channel.
+	///
+	/// See `audio_play_tone`.
+	pub audio_stop_tone: extern "C" fn(channel: u8) -> crate::ApiResult<()>,
+	/// Get how many samples are waiting to be played.
+	///
+	/// This is the number of samples in the current format that have been
+	/// accepted by `audio_output_data` but not yet played out. Dividing it by
+	/// the sample rate gives the time until the most recently sent sample is
+	/// heard, which is useful for keeping audio in sync with video.
+	///
+	/// Returns `0` when the output FIFO is empty (i.e. when it has
+	/// underflowed and silence is being played).
+	pub audio_output_get_latency: extern "C" fn() -> crate::ApiResult<u32>,
+	/// Mute, or un-mute, an Audio Mixer Channel.
+	///
+	/// The channel's level is kept, so un-muting it restores the previous
+	/// volume exactly. See the `muted` field of
+	/// [`audio::MixerChannelInfo`].
+	pub audio_mixer_channel_set_mute:
+		extern "C" fn(audio_mixer_id: u8, muted: bool) -> crate::ApiResult<()>,
+	/// Measure the signal level on an Audio Mixer Channel.
+	///
+	/// Returns the peak level seen since this function was last called for
+	/// this channel, from `0` (silence) to `255` (full scale). For an output
+	/// channel this is the signal being played, and for an input channel it
+	/// is the signal being captured. This is not the same as the volume set
+	/// with `audio_mixer_channel_set_level` - it is intended for drawing VU
+	/// meters.
+	///
+	/// A BIOS which cannot measure signal levels returns
+	/// `Error::Unimplemented`.
+	pub audio_mixer_channel_get_level: extern "C" fn(audio_mixer_id: u8) -> crate::ApiResult<u8>,
+	/// Stop, or re-start, the audio output.
+	///
+	/// Unlike `audio_output_set_config`, this keeps the current
+	/// configuration, so the OS doesn't have to set it up again to re-start
+	/// the output. Disabling the output throws away anything waiting in the
+	/// output FIFO, and lets the BIOS save power (e.g. by stopping the clock
+	/// to the audio hardware). When the output is enabled again, it starts
+	/// with silence.
+	pub audio_output_set_enabled: extern "C" fn(enabled: bool) -> crate::ApiResult<()>,
+	/// Stop, or re-start, the audio input.
+	///
+	/// As `audio_output_set_enabled`, but for the input. Disabling the input
+	/// throws away anything waiting in the input FIFO.
+	pub audio_input_set_enabled: extern "C" fn(enabled: bool) -> crate::ApiResult<()>,
+	/// Check whether the audio output could use a configuration.
+	///
+	/// Returns the configuration that `audio_output_set_config` would
+	/// actually use if given `config` - in particular, with the sample rate
+	/// changed to the nearest one the hardware can achieve. If the
+	/// configuration couldn't be used at all, the same error that
+	/// `audio_output_set_config` would return is returned instead.
+	///
+	/// Unlike `audio_output_set_config`, this doesn't change the audio
+	/// output or flush its FIFO, so it can be used to probe for supported
+	/// configurations without any audible glitches.
+	pub audio_output_query_config:
+		extern "C" fn(config: audio::Config) -> crate::ApiResult<audio::Config>,
+	/// Check whether the audio input could use a configuration.
+	///
+	/// As `audio_output_query_config`, but for `audio_input_set_config`.
+	pub audio_input_query_config:
+		extern "C" fn(config: audio::Config) -> crate::ApiResult<audio::Config>,
 
 	// ========================================================================
 	// Neotron (SPI) Bus Support
@@ -545,6 +1192,12 @@ pub struct Api {
 	/// the bus whilst waiting for things to happen (if your peripheral can
 	/// tolerate the CS line being de-activated at that time).
 	pub bus_select: extern "C" fn(peripheral_id: crate::FfiOption<u8>),
+	/// Find out which Neotron Bus Peripheral is currently selected.
+	///
+	/// Returns `None` if no peripheral is selected. This lets a driver
+	/// remember the current selection, select its own peripheral, and then
+	/// put the previous selection back afterwards with `bus_select`.
+	pub bus_get_selected: extern "C" fn() -> crate::FfiOption<u8>,
 	/// Find out some details about each particular Neotron Bus Peripheral.
 	pub bus_get_info: extern "C" fn(peripheral_id: u8) -> crate::FfiOption<bus::PeripheralInfo>,
 	/// Transact with the currently selected Neotron Bus Peripheral.
@@ -605,9 +1258,69 @@ pub struct Api {
 	/// Get bus interrupt status.
 	///
 	/// Up to 32 interrupts can be returned as a single 32-bit value. A bit is
-	/// set when the interrupt is pending. There is no masking - ignore the bits
-	/// you don't care about.
+	/// set when the interrupt is pending. Every pending interrupt is returned,
+	/// regardless of the mask set with `bus_set_interrupt_mask` - see
+	/// `bus_interrupt_status_masked` if you only want those.
+	///
+	/// The `interrupt_bit` field of [`bus::PeripheralInfo`] says which bit
+	/// belongs to which peripheral, and `thermal_get_interrupt_bit` says
+	/// which bit is the thermal interrupt.
 	pub bus_interrupt_status: extern "C" fn() -> u32,
+	/// Set the clock speed and SPI mode for the currently selected Neotron
+	/// Bus Peripheral.
+	///
+	/// The BIOS picks the fastest clock speed it can achieve which is no
+	/// faster than `clock_hz`, and returns that speed. For example, an SD Card
+	/// must be initialised at 400 kHz or less, but can then run much faster.
+	///
+	/// The settings are remembered for each peripheral, and are put back
+	/// whenever that peripheral is selected with `bus_select`. If no
+	/// peripheral is selected, the settings apply to unselected traffic.
+	pub bus_configure: extern "C" fn(clock_hz: u32, mode: bus::FfiSpiMode) -> crate::ApiResult<u32>,
+	/// Send and receive bytes with the currently selected Neotron Bus
+	/// Peripheral, using separate buffers.
+	///
+	/// You should select a peripheral with `bus_select` first,
+	/// however you can send unselected traffic (e.g. to configure an SD Card
+	/// into SPI mode).
+	///
+	/// SPI is full-duplex, and this routine clocks out the bytes in `tx`
+	/// whilst filling `rx` with the bytes received from the peripheral. The
+	/// transfer is as long as the longer of the two buffers - if `tx` is
+	/// shorter, `0xFF` bytes are clocked out after it, and if `rx` is
+	/// shorter, the extra received bytes are discarded.
+	///
+	/// ```no_run
+	/// # let api = neotron_common_bios::Api::make_dummy_api().unwrap();
+	/// # use neotron_common_bios::{FfiByteSlice, FfiBuffer, FfiOption};
+	/// // Grab Peripheral 1 on the bus
+	/// let _ = (api.bus_select)(FfiOption::Some(1));
+	/// // Send a two byte command, and collect the six bytes received
+	/// // at the same time
+	/// let mut buf = [0u8; 6];
+	/// let _ = (api.bus_transfer)(FfiByteSlice::new(&[0x9F, 0x00]), FfiBuffer::new(&mut buf));
+	/// // buf now contains whatever the peripheral sent us.
+	/// // Release the bus
+	/// let _ = (api.bus_select)(FfiOption::None);
+	/// # Ok::<(), neotron_common_bios::Error>(())
+	/// ```
+	pub bus_transfer: extern "C" fn(tx: FfiByteSlice, rx: FfiBuffer) -> crate::ApiResult<()>,
+	/// Set which Neotron Bus interrupts the OS is interested in.
+	///
+	/// Each bit in `mask` corresponds to a bit in `bus_interrupt_status`. An
+	/// interrupt whose bit is clear is still recorded as pending, but it will
+	/// not wake the system from `power_idle`, and it is not reported by
+	/// `bus_interrupt_status_masked`.
+	///
+	/// At start-up, the mask is `0xFFFF_FFFF` (all interrupts of interest).
+	pub bus_set_interrupt_mask: extern "C" fn(mask: u32),
+	/// Get the mask set by `bus_set_interrupt_mask`.
+	pub bus_get_interrupt_mask: extern "C" fn() -> u32,
+	/// Get bus interrupt status, for the interrupts of interest.
+	///
+	/// This is the value from `bus_interrupt_status`, ANDed with the mask
+	/// from `bus_get_interrupt_mask`.
+	pub bus_interrupt_status_masked: extern "C" fn() -> u32,
 
 	// ========================================================================
 	// Block Device Support
@@ -672,6 +1385,71 @@ pub struct Api {
 		num_blocks: u8,
 		data: FfiByteSlice,
 	) -> crate::ApiResult<()>,
+	/// Make sure everything written to a block device has reached the media.
+	///
+	/// A BIOS (or the device itself) may cache writes, so `block_write`
+	/// returning only means the data has been handed over. This function
+	/// blocks until all cached writes are stored on the physical media. The
+	/// OS should call this before reporting that a file has been safely
+	/// saved, or before the media is removed.
+	///
+	/// On a device without a write cache, this does nothing and returns
+	/// `Ok(())`.
+	pub block_dev_flush: extern "C" fn(device_id: u8) -> crate::ApiResult<()>,
+	/// Tell a block device that some blocks are no longer in use.
+	///
+	/// This issues a TRIM (or ERASE) command, which helps SD cards and other
+	/// flash devices to perform well and wear evenly. Discarded blocks may
+	/// read back as zeros, or with their previous contents - this is not a
+	/// secure erase.
+	///
+	/// A device which does not support this returns `Error::Unimplemented`.
+	pub block_dev_discard: extern "C" fn(
+		device_id: u8,
+		start_block: block_dev::BlockIdx,
+		num_blocks: u32,
+	) -> crate::ApiResult<()>,
+	/// Get a unique identifier for the media in a block device.
+	///
+	/// The identifier is whatever the device provides (e.g. the serial
+	/// number from an SD Card's CID register, or a hard drive's serial
+	/// number), so the OS should treat it as an opaque sequence of bytes. It does not change
+	/// when the media is removed and re-inserted, so the OS can use it to
+	/// recognise the media again.
+	///
+	/// As much of the identifier as will fit is copied into `out`, and the
+	/// full length of the identifier is returned. If that is larger than
+	/// `out`, the identifier has been truncated - as with
+	/// `configuration_get`. A device without an identifier returns
+	/// `Error::Unimplemented`.
+	pub block_dev_get_id: extern "C" fn(device_id: u8, out: FfiBuffer) -> crate::ApiResult<usize>,
+	/// Read the Table of Contents from an optical disc.
+	///
+	/// The Table of Contents lists the tracks on the disc, and where each
+	/// one starts. It is copied into `out` in the format returned by the MMC
+	/// `READ TOC/PMA/ATIP` command (format `0000b`, with LBA addresses) - a
+	/// four byte header, then an eight byte descriptor for each track,
+	/// ending with the lead-out (track `0xAA`). Returns the number of bytes
+	/// written. If `out` is too short, the table is truncated.
+	///
+	/// The data sectors of the disc can be read with `block_read` as usual.
+	/// Devices which are not a `block_dev::DeviceType::OpticalDisc` return
+	/// `Error::Unimplemented`, and an empty drive returns
+	/// `Error::NoMediaFound`.
+	pub block_dev_read_toc: extern "C" fn(device_id: u8, out: FfiBuffer) -> crate::ApiResult<usize>,
+	/// Set how long operations on a block device may take.
+	///
+	/// If a `block_read`, `block_write`, `block_verify`, `block_dev_flush` or
+	/// `block_dev_discard` call on this device takes longer than `timeout`,
+	/// it is abandoned and `Error::Timeout` is returned. This lets the OS
+	/// recover if, for example, an SD card is removed part-way through a
+	/// transfer, rather than waiting forever.
+	///
+	/// Passing `None` restores the BIOS's default timeout for the device.
+	/// Passing `Some(Timeout::UNBOUNDED)` turns the timeout off, so
+	/// operations on this device wait forever.
+	pub block_dev_set_timeout:
+		extern "C" fn(device_id: u8, timeout: crate::FfiOption<Timeout>) -> crate::ApiResult<()>,
 
 	// ========================================================================
 	// Power management functions
@@ -686,7 +1464,85 @@ pub struct Api {
 	/// This function will not return, because the system will be switched off
 	/// before it can return. In the event on an error, this function will hang
 	/// instead.
+	///
+	/// Not every system can actually remove its own power - see
+	/// `power_can_poweroff`.
+	///
+	/// If the hardware watchdog is running (see `watchdog_configure`), it is
+	/// stopped first.
 	pub power_control: extern "C" fn(mode: FfiPowerMode) -> !,
+	/// Can this system actually switch its own power off?
+	///
+	/// If this returns `true`, calling `power_control` with `PowerMode::Off`
+	/// will physically cut the power.
+	///
+	/// If this returns `false` (e.g. on a development board, or in an
+	/// emulator), calling `power_control` with `PowerMode::Off` will only halt
+	/// the system - it still never returns, but the power stays on. The OS
+	/// might then tell the user "It is now safe to turn off your computer",
+	/// or offer a reboot instead.
+	pub power_can_poweroff: extern "C" fn() -> bool,
+	/// Get the state of the system's power supply and battery.
+	///
+	/// Systems which can only run from external power (e.g. a desktop
+	/// machine, or an emulator) return `None`.
+	pub power_get_status: extern "C" fn() -> crate::FfiOption<PowerStatus>,
+	/// Get the current thermal state of the system.
+	///
+	/// On systems which throttle their clock when they overheat, this lets
+	/// the OS find out why things have slowed down, and perhaps pause
+	/// background work or warn the user.
+	///
+	/// Whenever the thermal state changes, the BIOS raises an interrupt,
+	/// which the OS will see in `bus_interrupt_status` at the bit given by
+	/// `thermal_get_interrupt_bit`. Calling this function clears that
+	/// interrupt.
+	///
+	/// A system without a temperature sensor returns `Error::Unimplemented`.
+	pub thermal_get_state: extern "C" fn() -> crate::ApiResult<FfiThermalState>,
+	/// Which bit in the `bus_interrupt_status` value is the thermal
+	/// interrupt?
+	///
+	/// See `thermal_get_state`. A system without a temperature sensor returns
+	/// `None`.
+	pub thermal_get_interrupt_bit: extern "C" fn() -> crate::FfiOption<u8>,
+	/// Get the current CPU clock frequency, in Hz.
+	pub cpu_get_frequency_hz: extern "C" fn() -> u32,
+	/// Ask the BIOS to change the CPU clock to suit the given performance
+	/// level.
+	///
+	/// The BIOS maps each level onto whatever clock (and voltage) settings
+	/// the hardware supports, and returns the resulting CPU clock frequency,
+	/// in Hz. Where `power_idle` saves power when there is nothing to do,
+	/// this lets the OS save power when there is only a little to do.
+	///
+	/// A system with a fixed CPU clock returns `Error::Unimplemented`.
+	pub cpu_set_performance: extern "C" fn(level: FfiPerformanceLevel) -> crate::ApiResult<u32>,
+	/// Start (or re-start) the hardware watchdog.
+	///
+	/// Once started, the OS must call `watchdog_feed` at least once every
+	/// `timeout_ms` milliseconds. If it fails to do so, the BIOS assumes the
+	/// OS has hung and resets the system.
+	///
+	/// Most watchdogs only support a limited set of timeouts, so the BIOS
+	/// picks the nearest one it can do that isn't longer than what was asked
+	/// for, and returns the timeout actually granted, in milliseconds.
+	///
+	/// A system without a hardware watchdog returns `Error::Unimplemented`.
+	pub watchdog_configure: extern "C" fn(timeout_ms: u32) -> crate::ApiResult<u32>,
+	/// Feed the hardware watchdog, re-starting its countdown.
+	///
+	/// Does nothing if the watchdog is not running.
+	pub watchdog_feed: extern "C" fn(),
+	/// Stop the hardware watchdog.
+	///
+	/// Some watchdogs cannot be stopped once they have been started, in
+	/// which case `Error::Unimplemented` is returned and the OS must carry
+	/// on feeding it.
+	///
+	/// Calling `power_control` implicitly stops the watchdog, so it cannot
+	/// fire part-way through a shutdown or reboot.
+	pub watchdog_disable: extern "C" fn() -> crate::ApiResult<()>,
 
 	// ========================================================================
 	// Mutex functions
@@ -700,6 +1556,90 @@ pub struct Api {
 		old_value: bool,
 		new_value: bool,
 	) -> bool,
+
+	// ========================================================================
+	// Firmware Update Support
+	// ========================================================================
+	/// Start updating the BIOS firmware.
+	///
+	/// The BIOS prepares somewhere (e.g. a spare flash bank) to stage a new
+	/// firmware image of `total_size` bytes. The image is then sent with
+	/// `firmware_update_write` and applied with `firmware_update_finish`.
+	///
+	/// The running firmware is not touched until the new image has been
+	/// completely received and verified, so a failed or abandoned update
+	/// leaves the system able to boot as before.
+	///
+	/// A BIOS which cannot update itself returns `Error::Unimplemented`.
+	pub firmware_update_begin: extern "C" fn(total_size: usize) -> crate::ApiResult<()>,
+	/// Send part of a new firmware image.
+	///
+	/// The `data` is copied to the given byte `offset` within the image being
+	/// staged. See `firmware_update_begin`.
+	pub firmware_update_write:
+		extern "C" fn(offset: usize, data: FfiByteSlice) -> crate::ApiResult<()>,
+	/// Finish updating the BIOS firmware.
+	///
+	/// The BIOS checks the staged image against the given CRC-32 and, if it
+	/// matches, reboots into the new firmware - so on success, this function
+	/// does not return. If the image is incomplete or does not match, an
+	/// error is returned and the running firmware is left intact.
+	pub firmware_update_finish: extern "C" fn(crc32: u32) -> crate::ApiResult<()>,
+	/// Find the region of flash which the OS is allowed to re-program with
+	/// `firmware_erase` and `firmware_write`.
+	///
+	/// Offsets given to those functions are relative to the start of this
+	/// region. Returns `None` if the BIOS cannot program its own flash.
+	pub firmware_region: extern "C" fn() -> crate::FfiOption<MemoryRegion>,
+	/// Erase part of the updatable flash region.
+	///
+	/// Both `offset` and `length` must be multiples of the flash sector size,
+	/// otherwise `Error::UnsupportedConfiguration` is returned. Erasing past
+	/// the end of the region returns `Error::BlockOutOfBounds`.
+	///
+	/// This is a much lower-level interface than `firmware_update_begin` -
+	/// there is no staging and no verification. If you erase the firmware and
+	/// then lose power, the system may not boot again.
+	///
+	/// A BIOS which cannot program its own flash returns
+	/// `Error::Unimplemented`.
+	pub firmware_erase: extern "C" fn(offset: u32, length: u32) -> crate::ApiResult<()>,
+	/// Write to part of the updatable flash region.
+	///
+	/// The area must have been erased with `firmware_erase` first. The
+	/// `offset` must be aligned to the flash page size, otherwise
+	/// `Error::UnsupportedConfiguration` is returned.
+	///
+	/// A BIOS which cannot program its own flash returns
+	/// `Error::Unimplemented`.
+	///
+	/// # Safety
+	///
+	/// The caller must not write over any code or data that the running BIOS
+	/// (or OS) is currently using - that is undefined behaviour. It is up to
+	/// the caller to know what lives where in the region.
+	pub firmware_write:
+		unsafe extern "C" fn(offset: u32, data: FfiByteSlice) -> crate::ApiResult<()>,
+
+	// ========================================================================
+	// Random Number Support
+	// ========================================================================
+	/// Fill a buffer with random bytes.
+	///
+	/// The BIOS uses the best source of entropy it has - an on-chip true
+	/// random number generator if there is one, otherwise something like
+	/// ring-oscillator jitter or ADC noise. Returns how many bytes of the
+	/// buffer were filled, which may be fewer than asked for if the entropy
+	/// source is slow.
+	///
+	/// If `random_is_secure` returns `true`, these bytes are suitable for
+	/// cryptographic use. Otherwise they are only a best-effort source of
+	/// randomness - fine for seeding a hash map, but not for generating keys.
+	///
+	/// A system with no entropy source at all returns `Error::Unimplemented`.
+	pub random_get: extern "C" fn(buffer: FfiBuffer) -> crate::ApiResult<usize>,
+	/// Are the bytes from `random_get` cryptographically secure?
+	pub random_is_secure: extern "C" fn() -> bool,
 }
 
 // ============================================================================
@@ -707,6 +1647,164 @@ pub struct Api {
 // ============================================================================
 
 impl Api {
+	/// The name and byte offset of every field in this structure, in the
+	/// order they are declared.
+	///
+	/// The BIOS and the OS are compiled separately, so they must agree on
+	/// where each function pointer lives. Every field is a function pointer,
+	/// so field `n` lives at `n * size_of::<usize>()` - that is, at `4 * n`
+	/// on a 32-bit system. Tools can compare this table against one from
+	/// another build of this crate.
+	pub const FIELD_OFFSETS: &[(&str, usize)] = api_field_offsets!(
+		api_version_get,
+		bios_version_get,
+		api_layout_hash,
+		serial_get_info,
+		serial_configure,
+		serial_write,
+		serial_read,
+		serial_transaction,
+		serial_read_tagged,
+		serial_flush,
+		serial_set_control_lines,
+		serial_get_status_lines,
+		serial_send_break,
+		serial_get_rx_count,
+		serial_get_tx_space,
+		time_clock_get,
+		time_clock_set,
+		time_ticks_get,
+		time_ticks_per_second,
+		delay_ms,
+		delay_us,
+		time_alarm_set,
+		time_alarm_clear,
+		configuration_get,
+		configuration_set,
+		configuration_get_max_len,
+		system_name_get,
+		system_name_set,
+		machine_get_unique_id,
+		video_is_valid_mode,
+		video_mode_needs_vram,
+		video_set_mode,
+		video_get_mode,
+		video_get_framebuffer,
+		video_wait_for_line,
+		video_get_palette,
+		video_set_palette,
+		video_set_whole_palette,
+		video_cycle_palette,
+		video_get_palette_size,
+		video_show_test_pattern,
+		video_get_supported_mode,
+		video_set_cursor,
+		video_set_cursor_style,
+		video_set_font,
+		video_reset_font,
+		video_register_framebuffer,
+		video_flip_framebuffer,
+		video_get_whole_palette,
+		video_get_blink_phase,
+		video_fill_rect,
+		video_blit,
+		video_set_line_interrupt,
+		memory_get_region,
+		hid_get_event,
+		hid_set_leds,
+		hid_peek_event,
+		hid_get_modifiers,
+		hid_get_device_info,
+		hid_set_repeat_config,
+		hid_set_raw_mode,
+		i2c_bus_get_info,
+		i2c_write_read,
+		i2c_lock,
+		i2c_unlock,
+		i2c_bus_scan,
+		i2c_bus_set_speed,
+		i2c_bus_get_speed,
+		audio_mixer_channel_get_info,
+		audio_mixer_channel_set_level,
+		audio_output_set_config,
+		audio_output_get_config,
+		audio_output_data,
+		audio_output_get_space,
+		audio_input_set_config,
+		audio_input_get_config,
+		audio_input_data,
+		audio_input_get_count,
+		audio_set_monitor,
+		audio_play_tone,
+		audio_stop_tone,
+		audio_output_get_latency,
+		audio_mixer_channel_set_mute,
+		audio_mixer_channel_get_level,
+		audio_output_set_enabled,
+		audio_input_set_enabled,
+		audio_output_query_config,
+		audio_input_query_config,
+		bus_select,
+		bus_get_selected,
+		bus_get_info,
+		bus_write_read,
+		bus_exchange,
+		bus_interrupt_status,
+		bus_configure,
+		bus_transfer,
+		bus_set_interrupt_mask,
+		bus_get_interrupt_mask,
+		bus_interrupt_status_masked,
+		block_dev_get_info,
+		block_dev_eject,
+		block_write,
+		block_read,
+		block_verify,
+		block_dev_flush,
+		block_dev_discard,
+		block_dev_get_id,
+		block_dev_read_toc,
+		block_dev_set_timeout,
+		power_idle,
+		power_control,
+		power_can_poweroff,
+		power_get_status,
+		thermal_get_state,
+		thermal_get_interrupt_bit,
+		cpu_get_frequency_hz,
+		cpu_set_performance,
+		watchdog_configure,
+		watchdog_feed,
+		watchdog_disable,
+		compare_and_swap_bool,
+		firmware_update_begin,
+		firmware_update_write,
+		firmware_update_finish,
+		firmware_region,
+		firmware_erase,
+		firmware_write,
+		random_get,
+		random_is_secure,
+	);
+
+	/// Calculate a hash of the layout of this structure.
+	///
+	/// This is a 32-bit FNV-1a hash over the name and offset of every field,
+	/// in order (see [`Api::FIELD_OFFSETS`]). It changes if a field is
+	/// added, removed, renamed or moved, but not if only a function's
+	/// arguments change - that is what [`API_VERSION`] is for.
+	pub const fn layout_hash() -> u32 {
+		let mut hash = FNV_OFFSET_BASIS;
+		let mut idx = 0;
+		while idx < Self::FIELD_OFFSETS.len() {
+			let (name, offset) = Self::FIELD_OFFSETS[idx];
+			hash = fnv1a(hash, name.as_bytes());
+			hash = fnv1a(hash, &(offset as u32).to_le_bytes());
+			idx += 1;
+		}
+		hash
+	}
+
 	/// This function only exists to make the doctests compile.
 	///
 	/// It always returns `None`.
@@ -716,6 +1814,649 @@ impl Api {
 	}
 }
 
+// ============================================================================
+// Functions
+// ============================================================================
+
+/// Feed some bytes into a 32-bit FNV-1a hash.
+const fn fnv1a(mut hash: u32, bytes: &[u8]) -> u32 {
+	let mut idx = 0;
+	while idx < bytes.len() {
+		hash ^= bytes[idx] as u32;
+		hash = hash.wrapping_mul(FNV_PRIME);
+		idx += 1;
+	}
+	hash
+}
+
+/// Reports the size and alignment of every type passed across the [`Api`].
+///
+/// Each entry is `(name, size, alignment)`, in bytes. The BIOS and the OS
+/// are compiled separately, so tools can compare this report against one
+/// from another build of this crate to catch accidental layout changes.
+pub fn abi_layout_report() -> &'static [(&'static str, usize, usize)] {
+	const LAYOUTS: &[(&str, usize, usize)] = abi_layout!(
+		Version,
+		FfiString,
+		FfiByteSlice,
+		FfiBuffer,
+		Error,
+		Timeout,
+		FfiDuration,
+		Time,
+		Ticks,
+		MemoryRegion,
+		FfiMemoryKind,
+		FfiPowerMode,
+		PowerStatus,
+		FfiThermalState,
+		FfiPerformanceLevel,
+		serial::Config,
+		serial::DeviceInfo,
+		serial::DataRates,
+		serial::ByteStatus,
+		serial::ControlLines,
+		serial::StatusLines,
+		video::Mode,
+		video::RGBColour,
+		video::FfiTestPattern,
+		video::FfiCursorStyle,
+		hid::HidEvent,
+		hid::MouseData,
+		hid::PointerData,
+		hid::GamepadData,
+		hid::GamepadButtons,
+		hid::KeyboardLeds,
+		hid::Modifiers,
+		hid::DeviceInfo,
+		hid::FfiDeviceKind,
+		i2c::BusInfo,
+		audio::Config,
+		audio::MixerChannelInfo,
+		bus::PeripheralInfo,
+		bus::FfiSpiMode,
+		block_dev::DeviceInfo,
+		block_dev::BlockIdx,
+	);
+	LAYOUTS
+}
+
+// The 32-bit layouts from the `abi_layouts` test, checked at compile time so
+// that a cross-build for a 32-bit BIOS target fails if any of them change.
+#[cfg(target_pointer_width = "32")]
+assert_abi_layout!(
+	Version => (4, 4),
+	FfiString => (8, 4),
+	FfiByteSlice => (8, 4),
+	FfiBuffer => (8, 4),
+	Error => (1, 1),
+	Timeout => (4, 4),
+	FfiDuration => (8, 4),
+	Time => (8, 4),
+	Ticks => (8, 8),
+	MemoryRegion => (12, 4),
+	FfiMemoryKind => (1, 1),
+	FfiPowerMode => (1, 1),
+	PowerStatus => (3, 1),
+	FfiThermalState => (1, 1),
+	FfiPerformanceLevel => (1, 1),
+	serial::Config => (8, 4),
+	serial::DeviceInfo => (32, 4),
+	serial::DataRates => (8, 4),
+	serial::ByteStatus => (1, 1),
+	serial::ControlLines => (1, 1),
+	serial::StatusLines => (1, 1),
+	video::Mode => (1, 1),
+	video::RGBColour => (4, 4),
+	video::FfiTestPattern => (1, 1),
+	video::FfiCursorStyle => (1, 1),
+	hid::HidEvent => (12, 4),
+	hid::MouseData => (6, 2),
+	hid::PointerData => (8, 2),
+	hid::GamepadData => (8, 2),
+	hid::GamepadButtons => (2, 2),
+	hid::KeyboardLeds => (1, 1),
+	hid::Modifiers => (2, 2),
+	hid::DeviceInfo => (12, 4),
+	hid::FfiDeviceKind => (1, 1),
+	i2c::BusInfo => (16, 4),
+	audio::Config => (8, 4),
+	audio::MixerChannelInfo => (12, 4),
+	bus::PeripheralInfo => (20, 4),
+	bus::FfiSpiMode => (1, 1),
+	block_dev::DeviceInfo => (32, 8),
+	block_dev::BlockIdx => (8, 8),
+	Api => (516, 4),
+);
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn field_offsets_cover_api() {
+		let pointer_size = core::mem::size_of::<usize>();
+		for (idx, (name, offset)) in Api::FIELD_OFFSETS.iter().enumerate() {
+			assert_eq!(*offset, idx * pointer_size, "{} is out of order", name);
+		}
+		assert_eq!(
+			Api::FIELD_OFFSETS.len() * pointer_size,
+			core::mem::size_of::<Api>()
+		);
+	}
+
+	#[test]
+	fn field_offsets_golden() {
+		// Where every function pointer lives on a 32-bit system. BIOSes
+		// and OSes built with different versions of this crate only work
+		// together if they agree on this table, so if this test fails you
+		// have changed the ABI and must bump `API_VERSION`. This table is
+		// also the reference for anyone implementing the BIOS API in
+		// another language.
+		#[rustfmt::skip]
+		let expected: &[(&str, usize)] = &[
+			("api_version_get", 0),
+			("bios_version_get", 4),
+			("api_layout_hash", 8),
+			("serial_get_info", 12),
+			("serial_configure", 16),
+			("serial_write", 20),
+			("serial_read", 24),
+			("serial_transaction", 28),
+			("serial_read_tagged", 32),
+			("serial_flush", 36),
+			("serial_set_control_lines", 40),
+			("serial_get_status_lines", 44),
+			("serial_send_break", 48),
+			("serial_get_rx_count", 52),
+			("serial_get_tx_space", 56),
+			("time_clock_get", 60),
+			("time_clock_set", 64),
+			("time_ticks_get", 68),
+			("time_ticks_per_second", 72),
+			("delay_ms", 76),
+			("delay_us", 80),
+			("time_alarm_set", 84),
+			("time_alarm_clear", 88),
+			("configuration_get", 92),
+			("configuration_set", 96),
+			("configuration_get_max_len", 100),
+			("system_name_get", 104),
+			("system_name_set", 108),
+			("machine_get_unique_id", 112),
+			("video_is_valid_mode", 116),
+			("video_mode_needs_vram", 120),
+			("video_set_mode", 124),
+			("video_get_mode", 128),
+			("video_get_framebuffer", 132),
+			("video_wait_for_line", 136),
+			("video_get_palette", 140),
+			("video_set_palette", 144),
+			("video_set_whole_palette", 148),
+			("video_cycle_palette", 152),
+			("video_get_palette_size", 156),
+			("video_show_test_pattern", 160),
+			("video_get_supported_mode", 164),
+			("video_set_cursor", 168),
+			("video_set_cursor_style", 172),
+			("video_set_font", 176),
+			("video_reset_font", 180),
+			("video_register_framebuffer", 184),
+			("video_flip_framebuffer", 188),
+			("video_get_whole_palette", 192),
+			("video_get_blink_phase", 196),
+			("video_fill_rect", 200),
+			("video_blit", 204),
+			("video_set_line_interrupt", 208),
+			("memory_get_region", 212),
+			("hid_get_event", 216),
+			("hid_set_leds", 220),
+			("hid_peek_event", 224),
+			("hid_get_modifiers", 228),
+			("hid_get_device_info", 232),
+			("hid_set_repeat_config", 236),
+			("hid_set_raw_mode", 240),
+			("i2c_bus_get_info", 244),
+			("i2c_write_read", 248),
+			("i2c_lock", 252),
+			("i2c_unlock", 256),
+			("i2c_bus_scan", 260),
+			("i2c_bus_set_speed", 264),
+			("i2c_bus_get_speed", 268),
+			("audio_mixer_channel_get_info", 272),
+			("audio_mixer_channel_set_level", 276),
+			("audio_output_set_config", 280),
+			("audio_output_get_config", 284),
+			("audio_output_data", 288),
+			("audio_output_get_space", 292),
+			("audio_input_set_config", 296),
+			("audio_input_get_config", 300),
+			("audio_input_data", 304),
+			("audio_input_get_count", 308),
+			("audio_set_monitor", 312),
+			("audio_play_tone", 316),
+			("audio_stop_tone", 320),
+			("audio_output_get_latency", 324),
+			("audio_mixer_channel_set_mute", 328),
+			("audio_mixer_channel_get_level", 332),
+			("audio_output_set_enabled", 336),
+			("audio_input_set_enabled", 340),
+			("audio_output_query_config", 344),
+			("audio_input_query_config", 348),
+			("bus_select", 352),
+			("bus_get_selected", 356),
+			("bus_get_info", 360),
+			("bus_write_read", 364),
+			("bus_exchange", 368),
+			("bus_interrupt_status", 372),
+			("bus_configure", 376),
+			("bus_transfer", 380),
+			("bus_set_interrupt_mask", 384),
+			("bus_get_interrupt_mask", 388),
+			("bus_interrupt_status_masked", 392),
+			("block_dev_get_info", 396),
+			("block_dev_eject", 400),
+			("block_write", 404),
+			("block_read", 408),
+			("block_verify", 412),
+			("block_dev_flush", 416),
+			("block_dev_discard", 420),
+			("block_dev_get_id", 424),
+			("block_dev_read_toc", 428),
+			("block_dev_set_timeout", 432),
+			("power_idle", 436),
+			("power_control", 440),
+			("power_can_poweroff", 444),
+			("power_get_status", 448),
+			("thermal_get_state", 452),
+			("thermal_get_interrupt_bit", 456),
+			("cpu_get_frequency_hz", 460),
+			("cpu_set_performance", 464),
+			("watchdog_configure", 468),
+			("watchdog_feed", 472),
+			("watchdog_disable", 476),
+			("compare_and_swap_bool", 480),
+			("firmware_update_begin", 484),
+			("firmware_update_write", 488),
+			("firmware_update_finish", 492),
+			("firmware_region", 496),
+			("firmware_erase", 500),
+			("firmware_write", 504),
+			("random_get", 508),
+			("random_is_secure", 512),
+		];
+		assert_eq!(Api::FIELD_OFFSETS.len(), expected.len());
+		let pointer_size = core::mem::size_of::<usize>();
+		for (actual, expected) in Api::FIELD_OFFSETS.iter().zip(expected) {
+			let (name, offset_32) = *expected;
+			assert_eq!(*actual, (name, offset_32 / 4 * pointer_size));
+		}
+	}
+
+	#[test]
+	fn abi_layouts() {
+		// (name, 32-bit size, 32-bit alignment, 64-bit size, 64-bit alignment)
+		//
+		// Keep the 32-bit columns in sync with the `assert_abi_layout!` list.
+		#[rustfmt::skip]
+		let expected: &[(&str, usize, usize, usize, usize)] = &[
+			("Version", 4, 4, 4, 4),
+			("FfiString", 8, 4, 16, 8),
+			("FfiByteSlice", 8, 4, 16, 8),
+			("FfiBuffer", 8, 4, 16, 8),
+			("Error", 1, 1, 1, 1),
+			("Timeout", 4, 4, 4, 4),
+			("FfiDuration", 8, 4, 8, 4),
+			("Time", 8, 4, 8, 4),
+			("Ticks", 8, 8, 8, 8),
+			("MemoryRegion", 12, 4, 24, 8),
+			("FfiMemoryKind", 1, 1, 1, 1),
+			("FfiPowerMode", 1, 1, 1, 1),
+			("PowerStatus", 3, 1, 3, 1),
+			("FfiThermalState", 1, 1, 1, 1),
+			("FfiPerformanceLevel", 1, 1, 1, 1),
+			("serial::Config", 8, 4, 8, 4),
+			("serial::DeviceInfo", 32, 4, 56, 8),
+			("serial::DataRates", 8, 4, 16, 8),
+			("serial::ByteStatus", 1, 1, 1, 1),
+			("serial::ControlLines", 1, 1, 1, 1),
+			("serial::StatusLines", 1, 1, 1, 1),
+			("video::Mode", 1, 1, 1, 1),
+			("video::RGBColour", 4, 4, 4, 4),
+			("video::FfiTestPattern", 1, 1, 1, 1),
+			("video::FfiCursorStyle", 1, 1, 1, 1),
+			("hid::HidEvent", 12, 4, 12, 4),
+			("hid::MouseData", 6, 2, 6, 2),
+			("hid::PointerData", 8, 2, 8, 2),
+			("hid::GamepadData", 8, 2, 8, 2),
+			("hid::GamepadButtons", 2, 2, 2, 2),
+			("hid::KeyboardLeds", 1, 1, 1, 1),
+			("hid::Modifiers", 2, 2, 2, 2),
+			("hid::DeviceInfo", 12, 4, 24, 8),
+			("hid::FfiDeviceKind", 1, 1, 1, 1),
+			("i2c::BusInfo", 16, 4, 24, 8),
+			("audio::Config", 8, 4, 8, 4),
+			("audio::MixerChannelInfo", 12, 4, 24, 8),
+			("bus::PeripheralInfo", 20, 4, 32, 8),
+			("bus::FfiSpiMode", 1, 1, 1, 1),
+			("block_dev::DeviceInfo", 32, 8, 40, 8),
+			("block_dev::BlockIdx", 8, 8, 8, 8),
+		];
+		let report = abi_layout_report();
+		assert_eq!(report.len(), expected.len());
+		for (actual, expected) in report.iter().zip(expected) {
+			let (name, size_32, align_32, size_64, align_64) = *expected;
+			let (size, align) = if cfg!(target_pointer_width = "64") {
+				(size_64, align_64)
+			} else {
+				(size_32, align_32)
+			};
+			assert_eq!(*actual, (name, size, align));
+		}
+	}
+
+	#[cfg(feature = "defmt")]
+	#[test]
+	fn defmt_format_impls() {
+		fn is_format<T: defmt::Format>() {}
+		is_format::<Error>();
+		is_format::<Timeout>();
+		is_format::<Time>();
+		is_format::<Ticks>();
+		is_format::<MemoryRegion>();
+		is_format::<FfiMemoryKind>();
+		is_format::<PowerStatus>();
+		is_format::<Version>();
+		is_format::<serial::Config>();
+		is_format::<serial::DeviceInfo>();
+		is_format::<video::Mode>();
+		is_format::<video::RGBColour>();
+		is_format::<hid::HidEvent>();
+		is_format::<hid::DeviceInfo>();
+		is_format::<i2c::BusInfo>();
+		is_format::<audio::Config>();
+		is_format::<audio::MixerChannelInfo>();
+		is_format::<bus::PeripheralInfo>();
+		is_format::<block_dev::DeviceInfo>();
+		is_format::<block_dev::BlockIdx>();
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn serde_round_trip() {
+		let config = serial::Config::new(9600)
+			.data_bits(serial::DataBits::Seven)
+			.parity(serial::Parity::Even);
+		let json = serde_json::to_string(&config).unwrap();
+		assert_eq!(
+			serde_json::from_str::<serial::Config>(&json).unwrap(),
+			config
+		);
+
+		let mode = video::Mode::new(video::Timing::T640x480, video::Format::Text8x16);
+		let json = serde_json::to_string(&mode).unwrap();
+		assert_eq!(json, "0");
+		assert_eq!(serde_json::from_str::<video::Mode>(&json).unwrap(), mode);
+		assert!(serde_json::from_str::<video::Mode>("255").is_err());
+
+		let config = audio::Config {
+			sample_format: audio::SampleFormat::SixteenBitStereo.make_ffi_safe(),
+			sample_rate_hz: 44100,
+		};
+		let json = serde_json::to_string(&config).unwrap();
+		assert_eq!(
+			serde_json::from_str::<audio::Config>(&json).unwrap(),
+			config
+		);
+
+		let time = Time {
+			secs: 12345,
+			nsecs: 678,
+		};
+		let json = serde_json::to_string(&time).unwrap();
+		let parsed: Time = serde_json::from_str(&json).unwrap();
+		assert_eq!((parsed.secs, parsed.nsecs), (time.secs, time.nsecs));
+
+		let duration = FfiDuration::new(3, 250_000);
+		let json = serde_json::to_string(&duration).unwrap();
+		assert_eq!(json, r#"{"secs":3,"nanos":250000}"#);
+		assert_eq!(
+			serde_json::from_str::<FfiDuration>(&json).unwrap(),
+			duration
+		);
+		let unnormalised: FfiDuration =
+			serde_json::from_str(r#"{"secs":0,"nanos":2000000000}"#).unwrap();
+		assert_eq!(unnormalised, FfiDuration::new(2, 0));
+		assert_eq!(unnormalised.secs(), 2);
+		assert_eq!(unnormalised.subsec_nanos(), 0);
+	}
+
+	#[test]
+	fn fnv1a_hash() {
+		// Test vectors from the FNV reference implementation
+		assert_eq!(fnv1a(FNV_OFFSET_BASIS, b""), 0x811C_9DC5);
+		assert_eq!(fnv1a(FNV_OFFSET_BASIS, b"a"), 0xE40C_292C);
+		assert_eq!(fnv1a(FNV_OFFSET_BASIS, b"foobar"), 0xBF9C_F968);
+	}
+
+	#[test]
+	fn layout_hash() {
+		const HASH: u32 = Api::layout_hash();
+		// If this fails, the layout of `Api` has changed - check
+		// `field_offsets_golden` and update these values to match.
+		let expected = if cfg!(target_pointer_width = "64") {
+			0xE3A2_CC72
+		} else {
+			0xA109_AD30
+		};
+		assert_eq!(HASH, expected);
+		assert_eq!((stub::api().api_layout_hash)(), HASH);
+	}
+
+	#[test]
+	fn api_layout_hash_offset() {
+		// Must be the third field, straight after the two version calls, in
+		// every version of this crate.
+		assert_eq!(
+			core::mem::offset_of!(Api, api_layout_hash),
+			2 * core::mem::size_of::<usize>()
+		);
+	}
+
+	#[test]
+	fn serial_write_offset() {
+		// The sixth field, after the three version and layout calls and two
+		// serial calls.
+		assert_eq!(
+			core::mem::offset_of!(Api, serial_write),
+			5 * core::mem::size_of::<usize>()
+		);
+	}
+
+	#[test]
+	fn serial_transaction_loopback() {
+		let api = stub::api();
+		let request = [0x01, 0x03, 0x00, 0x10];
+		let mut reply = [0u8; 8];
+		let turnaround = FfiDuration::from_micros(350);
+		let timeout = FfiOption::Some(Timeout::new_ms(100));
+		assert_eq!(
+			(api.serial_transaction)(
+				0,
+				FfiByteSlice::new(&request),
+				FfiBuffer::new(&mut reply),
+				turnaround,
+				timeout.clone()
+			),
+			FfiResult::Ok(4)
+		);
+		assert_eq!(reply[0..4], request);
+		assert_eq!(
+			(api.serial_transaction)(
+				2,
+				FfiByteSlice::new(&request),
+				FfiBuffer::new(&mut reply),
+				turnaround,
+				timeout
+			),
+			FfiResult::Err(Error::InvalidDevice)
+		);
+	}
+
+	#[test]
+	fn system_name_round_trip() {
+		let api = stub::api();
+		let mut buffer = [0u8; SYSTEM_NAME_MAX_LEN];
+		assert_eq!(
+			(api.system_name_get)(FfiBuffer::new(&mut buffer)),
+			FfiResult::Ok(0)
+		);
+		assert_eq!(
+			(api.system_name_set)(FfiByteSlice::new(b"neotron-pico")),
+			FfiResult::Ok(())
+		);
+		assert_eq!(
+			(api.system_name_get)(FfiBuffer::new(&mut buffer)),
+			FfiResult::Ok(12)
+		);
+		assert_eq!(&buffer[0..12], b"neotron-pico");
+
+		let longest = [b'x'; SYSTEM_NAME_MAX_LEN];
+		assert_eq!(
+			(api.system_name_set)(FfiByteSlice::new(&longest)),
+			FfiResult::Ok(())
+		);
+		assert_eq!(
+			(api.system_name_get)(FfiBuffer::new(&mut buffer)),
+			FfiResult::Ok(SYSTEM_NAME_MAX_LEN)
+		);
+		assert_eq!(buffer, longest);
+
+		let too_long = [b'y'; SYSTEM_NAME_MAX_LEN + 1];
+		assert_eq!(
+			(api.system_name_set)(FfiByteSlice::new(&too_long)),
+			FfiResult::Err(Error::UnsupportedConfiguration)
+		);
+		assert_eq!(
+			(api.system_name_set)(FfiByteSlice::new("café".as_bytes())),
+			FfiResult::Err(Error::UnsupportedConfiguration)
+		);
+		assert_eq!(
+			(api.system_name_get)(FfiBuffer::new(&mut buffer)),
+			FfiResult::Ok(SYSTEM_NAME_MAX_LEN)
+		);
+		assert_eq!(buffer, longest);
+	}
+
+	#[test]
+	fn i2c_double_lock() {
+		let api = stub::api();
+		assert_eq!((api.i2c_lock)(0), FfiResult::Ok(()));
+		assert_eq!((api.i2c_lock)(0), FfiResult::Err(Error::Busy));
+		// Each bus has its own lock
+		assert_eq!((api.i2c_lock)(1), FfiResult::Ok(()));
+		assert_eq!((api.i2c_unlock)(0), FfiResult::Ok(()));
+		assert_eq!((api.i2c_lock)(0), FfiResult::Ok(()));
+		assert_eq!((api.i2c_lock)(2), FfiResult::Err(Error::InvalidDevice));
+	}
+
+	#[test]
+	fn bus_get_selected() {
+		let api = stub::api();
+		assert_eq!((api.bus_get_selected)(), FfiOption::None);
+		(api.bus_select)(FfiOption::Some(2));
+		assert_eq!((api.bus_get_selected)(), FfiOption::Some(2));
+		(api.bus_select)(FfiOption::None);
+		assert_eq!((api.bus_get_selected)(), FfiOption::None);
+	}
+
+	#[test]
+	fn serial_read_tagged_parity_error() {
+		let api = stub::api();
+		let timeout = FfiOption::Some(Timeout::new_ms(10));
+		let _ = (api.serial_write)(0, FfiByteSlice::new(b"ab"), timeout.clone());
+		stub::inject_parity_error(0);
+		let _ = (api.serial_write)(0, FfiByteSlice::new(b"c"), timeout.clone());
+		let mut data = [0u8; 4];
+		let mut status = [0xFFu8; 3];
+		assert_eq!(
+			(api.serial_read_tagged)(
+				0,
+				FfiBuffer::new(&mut data),
+				FfiBuffer::new(&mut status),
+				timeout.clone()
+			),
+			FfiResult::Err(Error::UnsupportedConfiguration)
+		);
+		assert_eq!(
+			(api.serial_read_tagged)(
+				0,
+				FfiBuffer::new(&mut data[0..3]),
+				FfiBuffer::new(&mut status),
+				timeout
+			),
+			FfiResult::Ok(3)
+		);
+		assert_eq!(&data[0..3], b"abc");
+		let status = status.map(serial::ByteStatus::from_u8);
+		assert!(!status[0].is_parity_error());
+		assert!(status[1].is_parity_error());
+		assert!(!status[1].is_framing_error());
+		assert_eq!(status[2], serial::ByteStatus::new());
+	}
+
+	#[test]
+	fn unique_ids_truncate() {
+		let api = stub::api();
+		let mut id = [0u8; MACHINE_ID_MAX_LEN];
+		let len = (api.machine_get_unique_id)(FfiBuffer::new(&mut id)).unwrap();
+		let mut short = [0u8; 3];
+		assert_eq!(
+			(api.machine_get_unique_id)(FfiBuffer::new(&mut short)),
+			FfiResult::Ok(len)
+		);
+		assert_eq!(short, id[0..3]);
+
+		let mut id = [0u8; 32];
+		let len = (api.block_dev_get_id)(0, FfiBuffer::new(&mut id)).unwrap();
+		assert!(len > short.len());
+		assert_eq!(
+			(api.block_dev_get_id)(0, FfiBuffer::new(&mut short)),
+			FfiResult::Ok(len)
+		);
+		assert_eq!(short, id[0..3]);
+		assert_eq!(
+			(api.block_dev_get_id)(1, FfiBuffer::new(&mut short)),
+			FfiResult::Err(Error::InvalidDevice)
+		);
+	}
+
+	#[test]
+	fn thermal_interrupt() {
+		let api = stub::api();
+		let bit = match (api.thermal_get_interrupt_bit)() {
+			FfiOption::Some(bit) => bit,
+			FfiOption::None => panic!("no thermal interrupt"),
+		};
+		let mask = 1 << bit;
+		assert_eq!((api.bus_interrupt_status)() & mask, 0);
+		stub::set_thermal_state(ThermalState::Throttling);
+		assert_eq!((api.bus_interrupt_status)() & mask, mask);
+		assert_eq!(
+			(api.thermal_get_state)(),
+			FfiResult::Ok(ThermalState::Throttling.make_ffi_safe())
+		);
+		assert_eq!((api.bus_interrupt_status)() & mask, 0);
+		// No change, so no interrupt
+		stub::set_thermal_state(ThermalState::Throttling);
+		assert_eq!((api.bus_interrupt_status)() & mask, 0);
+	}
+}
+
 // ============================================================================
 // End of File
 // ============================================================================
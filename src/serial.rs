@@ -90,7 +90,10 @@ make_ffi_enum!("The number of stop bits after each word.",
 	#[doc = "One stop bit is added to each word"]
 	One,
 	#[doc = "Two stop bits are added to each word"]
-	Two
+	Two,
+	#[doc = "One and a half stop bits are added to each word. This is typically"]
+	#[doc = "only used with 5 data bits."]
+	OnePointFive
 });
 
 make_ffi_enum!("The number of data bits in each word sent or received by the UART.",
@@ -100,12 +103,20 @@ make_ffi_enum!("The number of data bits in each word sent or received by the UAR
 	Seven,
 	#[doc = "Each word comprises 8 data bits (plus start bit, stop bits and any "]
 	#[doc = "parity bits"]
-	Eight
+	Eight,
+	#[doc = "Each word comprises 5 data bits (plus start bit, stop bits and any "]
+	#[doc = "parity bits). Typically used with Baudot teletypes."]
+	Five,
+	#[doc = "Each word comprises 6 data bits (plus start bit, stop bits and any "]
+	#[doc = "parity bits)."]
+	Six
 });
 
 /// A particular configuration for a serial port.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Config {
 	/// The desired transmission speed, in bits per second (also known as
 	/// the 'baud rate'). Some hardware implementations allow a free choice
@@ -131,13 +142,436 @@ pub struct DeviceInfo {
 	pub name: crate::FfiString<'static>,
 	/// The type of this serial device
 	pub device_type: FfiDeviceType,
+	/// The slowest data rate this device supports, in bits per second.
+	pub min_data_rate_bps: u32,
+	/// The fastest data rate this device supports, in bits per second.
+	pub max_data_rate_bps: u32,
+	/// The data rates this device supports, if it only supports a fixed set.
+	///
+	/// If this is `None`, the device can run at any rate between
+	/// `min_data_rate_bps` and `max_data_rate_bps`.
+	pub fixed_data_rates: crate::FfiOption<DataRates>,
 }
 
+/// A list of data rates, in bits per second.
+///
+/// This is an FFI-safe version of a `&'static [u32]`.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct DataRates {
+	data: *const u32,
+	len: usize,
+}
+
+/// The receive status of a single byte from a serial port.
+///
+/// See `Api::serial_read_tagged`.
+#[repr(transparent)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ByteStatus(u8);
+
+/// The modem control lines which a serial port can drive.
+///
+/// See `Api::serial_set_control_lines`.
+#[repr(transparent)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ControlLines(u8);
+
+/// The modem status lines which a serial port can sense.
+///
+/// See `Api::serial_get_status_lines`.
+#[repr(transparent)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatusLines(u8);
+
 // ============================================================================
 // Impls
 // ============================================================================
 
-// None
+impl DeviceInfo {
+	/// Can this device run at the given data rate?
+	///
+	/// ```
+	/// # use neotron_common_bios::{serial::*, FfiOption, FfiString};
+	/// static RATES: [u32; 3] = [9600, 57600, 115200];
+	/// let info = DeviceInfo {
+	///     name: FfiString::new("UART0"),
+	///     device_type: DeviceType::TtlUart.make_ffi_safe(),
+	///     min_data_rate_bps: 9600,
+	///     max_data_rate_bps: 115200,
+	///     fixed_data_rates: FfiOption::Some(DataRates::new(&RATES)),
+	/// };
+	/// assert!(info.supports_data_rate(57600));
+	/// assert!(!info.supports_data_rate(19200));
+	/// ```
+	pub fn supports_data_rate(&self, data_rate_bps: u32) -> bool {
+		if data_rate_bps < self.min_data_rate_bps || data_rate_bps > self.max_data_rate_bps {
+			return false;
+		}
+		match &self.fixed_data_rates {
+			crate::FfiOption::Some(rates) => rates.as_slice().contains(&data_rate_bps),
+			crate::FfiOption::None => true,
+		}
+	}
+}
+
+impl DataRates {
+	/// Make a new list of data rates.
+	pub const fn new(rates: &'static [u32]) -> DataRates {
+		DataRates {
+			data: rates.as_ptr(),
+			len: rates.len(),
+		}
+	}
+
+	/// Get the data rates in this list.
+	pub fn as_slice(&self) -> &'static [u32] {
+		// SAFETY: we were made from a valid `&'static [u32]`
+		unsafe { core::slice::from_raw_parts(self.data, self.len) }
+	}
+}
+
+impl core::fmt::Debug for DataRates {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		f.debug_list().entries(self.as_slice()).finish()
+	}
+}
+
+impl PartialEq for DataRates {
+	fn eq(&self, other: &DataRates) -> bool {
+		self.as_slice() == other.as_slice()
+	}
+}
+
+impl Eq for DataRates {}
+
+impl Config {
+	/// Create a new `Config` at the given data rate.
+	///
+	/// Everything else is as per [`Config::default()`], and can be changed
+	/// with the other methods on this type.
+	pub const fn new(data_rate_bps: u32) -> Self {
+		Config {
+			data_rate_bps,
+			data_bits: FfiDataBits::new(DataBits::Eight),
+			stop_bits: FfiStopBits::new(StopBits::One),
+			parity: FfiParity::new(Parity::None),
+			handshaking: FfiHandshaking::new(Handshaking::None),
+		}
+	}
+
+	/// Change the number of data bits.
+	pub const fn data_bits(mut self, data_bits: DataBits) -> Self {
+		self.data_bits = FfiDataBits::new(data_bits);
+		self
+	}
+
+	/// Change the number of stop bits.
+	pub const fn stop_bits(mut self, stop_bits: StopBits) -> Self {
+		self.stop_bits = FfiStopBits::new(stop_bits);
+		self
+	}
+
+	/// Change the parity configuration.
+	pub const fn parity(mut self, parity: Parity) -> Self {
+		self.parity = FfiParity::new(parity);
+		self
+	}
+
+	/// Change the handshaking configuration.
+	pub const fn handshaking(mut self, handshaking: Handshaking) -> Self {
+		self.handshaking = FfiHandshaking::new(handshaking);
+		self
+	}
+}
+
+impl Default for Config {
+	/// The default configuration is 115200 bps, 8 data bits, no parity, one
+	/// stop bit (8N1), with no handshaking.
+	fn default() -> Self {
+		Config::new(115_200)
+	}
+}
+
+impl ByteStatus {
+	const PARITY_ERROR_BIT: u8 = 1 << 0;
+	const FRAMING_ERROR_BIT: u8 = 1 << 1;
+	const BREAK_BIT: u8 = 1 << 2;
+
+	/// Create a new `ByteStatus` value.
+	///
+	/// All flags default to *not set*, meaning the byte was received cleanly.
+	pub const fn new() -> Self {
+		Self(0)
+	}
+
+	/// Convert from a raw status byte, as written by `serial_read_tagged`.
+	pub const fn from_u8(value: u8) -> Self {
+		Self(value)
+	}
+
+	/// Convert to a raw status byte.
+	pub const fn as_u8(self) -> u8 {
+		self.0
+	}
+
+	/// Note that the byte was received with the wrong parity.
+	pub const fn set_parity_error(self) -> Self {
+		let value = self.0 | Self::PARITY_ERROR_BIT;
+		Self(value)
+	}
+
+	/// Note that the byte was received without a valid stop bit.
+	pub const fn set_framing_error(self) -> Self {
+		let value = self.0 | Self::FRAMING_ERROR_BIT;
+		Self(value)
+	}
+
+	/// Note that a break condition was received in place of this byte.
+	pub const fn set_break(self) -> Self {
+		let value = self.0 | Self::BREAK_BIT;
+		Self(value)
+	}
+
+	/// Returns `true` if the byte was received with the wrong parity.
+	///
+	/// With *mark* or *space* parity, this can be used to spot the 9th
+	/// (address) bit.
+	pub const fn is_parity_error(self) -> bool {
+		self.0 & Self::PARITY_ERROR_BIT != 0
+	}
+
+	/// Returns `true` if the byte was received without a valid stop bit.
+	pub const fn is_framing_error(self) -> bool {
+		self.0 & Self::FRAMING_ERROR_BIT != 0
+	}
+
+	/// Returns `true` if a break condition was received in place of this byte.
+	pub const fn is_break(self) -> bool {
+		self.0 & Self::BREAK_BIT != 0
+	}
+}
+
+impl Default for ByteStatus {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl ControlLines {
+	const DTR_BIT: u8 = 1 << 0;
+	const RTS_BIT: u8 = 1 << 1;
+
+	/// Create a new `ControlLines` value.
+	///
+	/// All lines default to *not asserted*.
+	pub const fn new() -> Self {
+		Self(0)
+	}
+
+	/// Note that the *Data Terminal Ready* (DTR) line is asserted.
+	pub const fn set_dtr_asserted(self) -> Self {
+		let value = self.0 | Self::DTR_BIT;
+		Self(value)
+	}
+
+	/// Note that the *Request To Send* (RTS) line is asserted.
+	pub const fn set_rts_asserted(self) -> Self {
+		let value = self.0 | Self::RTS_BIT;
+		Self(value)
+	}
+
+	/// Returns `true` if the *Data Terminal Ready* (DTR) line is asserted.
+	pub const fn is_dtr_asserted(self) -> bool {
+		self.0 & Self::DTR_BIT != 0
+	}
+
+	/// Returns `true` if the *Request To Send* (RTS) line is asserted.
+	pub const fn is_rts_asserted(self) -> bool {
+		self.0 & Self::RTS_BIT != 0
+	}
+}
+
+impl Default for ControlLines {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl StatusLines {
+	const CTS_BIT: u8 = 1 << 0;
+	const DSR_BIT: u8 = 1 << 1;
+	const DCD_BIT: u8 = 1 << 2;
+	const RI_BIT: u8 = 1 << 3;
+
+	/// Create a new `StatusLines` value.
+	///
+	/// All lines default to *not asserted*.
+	pub const fn new() -> Self {
+		Self(0)
+	}
+
+	/// Note that the *Clear To Send* (CTS) line is asserted.
+	pub const fn set_cts_asserted(self) -> Self {
+		let value = self.0 | Self::CTS_BIT;
+		Self(value)
+	}
+
+	/// Note that the *Data Set Ready* (DSR) line is asserted.
+	pub const fn set_dsr_asserted(self) -> Self {
+		let value = self.0 | Self::DSR_BIT;
+		Self(value)
+	}
+
+	/// Note that the *Data Carrier Detect* (DCD) line is asserted.
+	pub const fn set_dcd_asserted(self) -> Self {
+		let value = self.0 | Self::DCD_BIT;
+		Self(value)
+	}
+
+	/// Note that the *Ring Indicator* (RI) line is asserted.
+	pub const fn set_ri_asserted(self) -> Self {
+		let value = self.0 | Self::RI_BIT;
+		Self(value)
+	}
+
+	/// Returns `true` if the *Clear To Send* (CTS) line is asserted.
+	pub const fn is_cts_asserted(self) -> bool {
+		self.0 & Self::CTS_BIT != 0
+	}
+
+	/// Returns `true` if the *Data Set Ready* (DSR) line is asserted.
+	pub const fn is_dsr_asserted(self) -> bool {
+		self.0 & Self::DSR_BIT != 0
+	}
+
+	/// Returns `true` if the *Data Carrier Detect* (DCD) line is asserted.
+	pub const fn is_dcd_asserted(self) -> bool {
+		self.0 & Self::DCD_BIT != 0
+	}
+
+	/// Returns `true` if the *Ring Indicator* (RI) line is asserted.
+	pub const fn is_ri_asserted(self) -> bool {
+		self.0 & Self::RI_BIT != 0
+	}
+}
+
+impl Default for StatusLines {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for DeviceInfo {
+	fn format(&self, f: defmt::Formatter) {
+		defmt::write!(
+			f,
+			"DeviceInfo {{ name: {=str}, device_type: {}, min_data_rate_bps: {}, max_data_rate_bps: {}, fixed_data_rates: {} }}",
+			self.name.as_str(),
+			self.device_type,
+			self.min_data_rate_bps,
+			self.max_data_rate_bps,
+			core::option::Option::<DataRates>::from(self.fixed_data_rates.clone())
+		)
+	}
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for DataRates {
+	fn format(&self, f: defmt::Formatter) {
+		defmt::write!(f, "{=[?]}", self.as_slice())
+	}
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn supports_data_rate() {
+		static RATES: [u32; 3] = [300, 1200, 9600];
+		let mut info = DeviceInfo {
+			name: crate::FfiString::new("COM1"),
+			device_type: DeviceType::Rs232.make_ffi_safe(),
+			min_data_rate_bps: 300,
+			max_data_rate_bps: 115_200,
+			fixed_data_rates: crate::FfiOption::None,
+		};
+		assert!(info.supports_data_rate(300));
+		assert!(info.supports_data_rate(31_250));
+		assert!(info.supports_data_rate(115_200));
+		assert!(!info.supports_data_rate(299));
+		assert!(!info.supports_data_rate(230_400));
+		info.fixed_data_rates = crate::FfiOption::Some(DataRates::new(&RATES));
+		assert!(info.supports_data_rate(1200));
+		assert!(!info.supports_data_rate(31_250));
+		assert_eq!(
+			info.fixed_data_rates,
+			crate::FfiOption::Some(DataRates::new(&[300, 1200, 9600]))
+		);
+	}
+
+	#[test]
+	fn config_default() {
+		let config = Config {
+			data_rate_bps: 115_200,
+			data_bits: DataBits::Eight.make_ffi_safe(),
+			stop_bits: StopBits::One.make_ffi_safe(),
+			parity: Parity::None.make_ffi_safe(),
+			handshaking: Handshaking::None.make_ffi_safe(),
+		};
+		assert_eq!(Config::default(), config);
+	}
+
+	#[test]
+	fn config_builder() {
+		let config = Config {
+			data_rate_bps: 9600,
+			data_bits: DataBits::Seven.make_ffi_safe(),
+			stop_bits: StopBits::Two.make_ffi_safe(),
+			parity: Parity::Even.make_ffi_safe(),
+			handshaking: Handshaking::RtsCts.make_ffi_safe(),
+		};
+		let built = Config::new(9600)
+			.data_bits(DataBits::Seven)
+			.stop_bits(StopBits::Two)
+			.parity(Parity::Even)
+			.handshaking(Handshaking::RtsCts);
+		assert_eq!(built, config);
+	}
+
+	#[test]
+	fn byte_status() {
+		let status = ByteStatus::new();
+		assert_eq!(status, ByteStatus::default());
+		assert_eq!(status.as_u8(), 0);
+		assert!(!status.is_parity_error());
+		assert!(!status.is_framing_error());
+		assert!(!status.is_break());
+		let status = status.set_parity_error();
+		assert!(status.is_parity_error());
+		assert!(!status.is_framing_error());
+		assert!(!status.is_break());
+		let status = status.set_framing_error().set_break();
+		assert!(status.is_parity_error());
+		assert!(status.is_framing_error());
+		assert!(status.is_break());
+		assert_eq!(ByteStatus::from_u8(status.as_u8()), status);
+		assert!(ByteStatus::from_u8(0b010).is_framing_error());
+		assert!(!ByteStatus::from_u8(0b010).is_break());
+	}
+}
 
 // ============================================================================
 // End of File
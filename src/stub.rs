@@ -0,0 +1,818 @@
+//! # Stub BIOS
+//!
+//! A pretend BIOS, for testing code which calls through the [`Api`].
+//!
+//! Serial devices are loopbacks - anything written to one can be read back
+//! from it. Every test runs on its own thread, and the stub keeps its state
+//! in thread-local storage, so tests cannot see each other's changes.
+//! Functions which no test needs just panic.
+
+// Copyright (C) The Neotron Developers, 2019-2022
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// ============================================================================
+// Imports
+// ============================================================================
+
+extern crate std;
+
+use std::{cell::RefCell, collections::VecDeque, vec::Vec};
+
+use crate::{
+	audio, block_dev, bus, hid, i2c, serial, video, Api, ApiResult, Error, FfiBuffer, FfiByteSlice,
+	FfiDuration, FfiOption, FfiPerformanceLevel, FfiPowerMode, FfiString, FfiThermalState,
+	MemoryKind, MemoryRegion, PowerStatus, ThermalState, Ticks, Time, Timeout, Version,
+};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// The serial devices in the stub BIOS.
+const SERIAL_PORTS: [(&str, serial::DeviceType, u32, u32); 2] = [
+	("COM1", serial::DeviceType::Rs232, 300, 115_200),
+	("MIDI", serial::DeviceType::Midi, 31_250, 31_250),
+];
+
+/// The regions of memory in the stub BIOS, as `(start, length, kind)`.
+const MEMORY_REGIONS: [(usize, usize, MemoryKind); 4] = [
+	(0x2000_0000, 0x4_0000, MemoryKind::Ram),
+	(0x1000_0000, 0x20_0000, MemoryKind::Rom),
+	(0x2004_0000, 0x1000, MemoryKind::Reserved),
+	(0x2100_0000, 0x1_0000, MemoryKind::Ram),
+];
+
+/// The I²C buses in the stub BIOS.
+const I2C_BUSES: [(&str, u32); 2] = [("I2C0", 400_000), ("DDC", 100_000)];
+
+/// The I²C Device Address of the EEPROM on I²C bus 0.
+const EEPROM_ADDRESS: u8 = 0x50;
+
+/// The Audio Mixer Channels in the stub BIOS.
+const AUDIO_MIXER_CHANNELS: [(&str, audio::Direction); 2] = [
+	("PCM Out", audio::Direction::Output),
+	("Line In", audio::Direction::Input),
+];
+
+/// The Neotron Bus Peripherals in the stub BIOS.
+const BUS_PERIPHERALS: [(&str, bus::PeripheralKind, Option<u8>); 2] = [
+	("Slot 0", bus::PeripheralKind::Slot, Some(0)),
+	("SD Card", bus::PeripheralKind::SdCard, None),
+];
+
+/// The block devices in the stub BIOS, as `(name, number of 512 byte
+/// blocks, media identifier)`.
+const BLOCK_DEVICES: [(&str, u64, &[u8]); 1] = [("SD Card", 65536, b"SD-0042-CAFE")];
+
+/// The factory-programmed unique ID of the stub BIOS's board.
+const MACHINE_ID: [u8; 8] = [0xE6, 0x60, 0x38, 0xB7, 0x13, 0x4F, 0x2A, 0x2F];
+
+/// The size of the stub BIOS's configuration data store, in bytes.
+const CONFIGURATION_MAX_LEN: usize = 32;
+
+/// Which bit in `bus_interrupt_status` is the thermal interrupt.
+const THERMAL_INTERRUPT_BIT: u8 = 7;
+
+/// How many entries there are in the stub BIOS's colour palette.
+const PALETTE_LEN: usize = 16;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Everything the stub BIOS remembers between calls.
+struct State {
+	/// Bytes written to each serial device, waiting to be read back, along
+	/// with the status they will be received with.
+	serial_rx: [VecDeque<(u8, serial::ByteStatus)>; SERIAL_PORTS.len()],
+	/// The configuration data block.
+	configuration: Vec<u8>,
+	/// Which I²C buses are locked.
+	i2c_locked: [bool; I2C_BUSES.len()],
+	/// The registers in the EEPROM on I²C bus 0.
+	eeprom: [u8; 8],
+	/// The system name.
+	system_name: Vec<u8>,
+	/// The selected Neotron Bus Peripheral.
+	bus_selected: Option<u8>,
+	/// The wall time.
+	clock: Time,
+	/// The monotonic system time.
+	ticks: Ticks,
+	/// The current video mode.
+	mode: video::Mode,
+	/// The VRAM given with the current video mode.
+	vram: *mut u32,
+	/// The colour palette.
+	palette: [video::RGBColour; PALETTE_LEN],
+	/// The current thermal state.
+	thermal: ThermalState,
+	/// The pending interrupts, as returned by `bus_interrupt_status`.
+	interrupts: u32,
+}
+
+// ============================================================================
+// Macros
+// ============================================================================
+
+/// Defines BIOS functions which no test calls.
+macro_rules! unused {
+	($(fn $name:ident($($arg:ty),*) $(-> $ret:ty)?;)*) => {$(
+		extern "C" fn $name($(_: $arg),*) $(-> $ret)? {
+			unimplemented!(stringify!($name))
+		}
+	)*};
+	($(unsafe fn $name:ident($($arg:ty),*) $(-> $ret:ty)?;)*) => {$(
+		unsafe extern "C" fn $name($(_: $arg),*) $(-> $ret)? {
+			unimplemented!(stringify!($name))
+		}
+	)*};
+}
+
+// ============================================================================
+// Statics
+// ============================================================================
+
+std::thread_local! {
+	static STATE: RefCell<State> = RefCell::new(State {
+		serial_rx: Default::default(),
+		configuration: Vec::new(),
+		system_name: Vec::new(),
+		i2c_locked: [false; I2C_BUSES.len()],
+		eeprom: [0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17],
+		bus_selected: None,
+		clock: Time { secs: 0, nsecs: 0 },
+		ticks: Ticks(0),
+		mode: video::Mode::new(video::Timing::T640x480, video::Format::Text8x16),
+		vram: core::ptr::null_mut(),
+		palette: [video::RGBColour::BLACK; PALETTE_LEN],
+		thermal: ThermalState::Normal,
+		interrupts: 0,
+	});
+}
+
+// ============================================================================
+// Functions
+// ============================================================================
+
+/// Get the API of the stub BIOS.
+pub(crate) fn api() -> Api {
+	Api {
+		api_version_get,
+		bios_version_get,
+		api_layout_hash,
+		serial_get_info,
+		serial_configure,
+		serial_write,
+		serial_read,
+		serial_transaction,
+		serial_read_tagged,
+		serial_flush,
+		serial_set_control_lines,
+		serial_get_status_lines,
+		serial_send_break,
+		serial_get_rx_count,
+		serial_get_tx_space,
+		time_clock_get,
+		time_clock_set,
+		time_ticks_get,
+		time_ticks_per_second,
+		delay_ms,
+		delay_us,
+		time_alarm_set,
+		time_alarm_clear,
+		configuration_get,
+		configuration_set,
+		configuration_get_max_len,
+		system_name_get,
+		system_name_set,
+		machine_get_unique_id,
+		video_is_valid_mode,
+		video_mode_needs_vram,
+		video_set_mode,
+		video_get_mode,
+		video_get_framebuffer,
+		video_wait_for_line,
+		video_get_palette,
+		video_set_palette,
+		video_set_whole_palette,
+		video_cycle_palette,
+		video_get_palette_size,
+		video_show_test_pattern,
+		video_get_supported_mode,
+		video_set_cursor,
+		video_set_cursor_style,
+		video_set_font,
+		video_reset_font,
+		video_register_framebuffer,
+		video_flip_framebuffer,
+		video_get_whole_palette,
+		video_get_blink_phase,
+		video_fill_rect,
+		video_blit,
+		video_set_line_interrupt,
+		memory_get_region,
+		hid_get_event,
+		hid_set_leds,
+		hid_peek_event,
+		hid_get_modifiers,
+		hid_get_device_info,
+		hid_set_repeat_config,
+		hid_set_raw_mode,
+		i2c_bus_get_info,
+		i2c_write_read,
+		i2c_lock,
+		i2c_unlock,
+		i2c_bus_scan,
+		i2c_bus_set_speed,
+		i2c_bus_get_speed,
+		audio_mixer_channel_get_info,
+		audio_mixer_channel_set_level,
+		audio_output_set_config,
+		audio_output_get_config,
+		audio_output_data,
+		audio_output_get_space,
+		audio_input_set_config,
+		audio_input_get_config,
+		audio_input_data,
+		audio_input_get_count,
+		audio_set_monitor,
+		audio_play_tone,
+		audio_stop_tone,
+		audio_output_get_latency,
+		audio_mixer_channel_set_mute,
+		audio_mixer_channel_get_level,
+		audio_output_set_enabled,
+		audio_input_set_enabled,
+		audio_output_query_config,
+		audio_input_query_config,
+		bus_select,
+		bus_get_selected,
+		bus_get_info,
+		bus_write_read,
+		bus_exchange,
+		bus_interrupt_status,
+		bus_configure,
+		bus_transfer,
+		bus_set_interrupt_mask,
+		bus_get_interrupt_mask,
+		bus_interrupt_status_masked,
+		block_dev_get_info,
+		block_dev_eject,
+		block_write,
+		block_read,
+		block_verify,
+		block_dev_flush,
+		block_dev_discard,
+		block_dev_get_id,
+		block_dev_read_toc,
+		block_dev_set_timeout,
+		power_idle,
+		power_control,
+		power_can_poweroff,
+		power_get_status,
+		thermal_get_state,
+		thermal_get_interrupt_bit,
+		cpu_get_frequency_hz,
+		cpu_set_performance,
+		watchdog_configure,
+		watchdog_feed,
+		watchdog_disable,
+		compare_and_swap_bool,
+		firmware_update_begin,
+		firmware_update_write,
+		firmware_update_finish,
+		firmware_region,
+		firmware_erase,
+		firmware_write,
+		random_get,
+		random_is_secure,
+	}
+}
+
+/// Run some code with the state of the stub BIOS.
+fn with_state<T>(f: impl FnOnce(&mut State) -> T) -> T {
+	STATE.with(|state| f(&mut state.borrow_mut()))
+}
+
+/// Pretend the last byte written to a serial device comes back with a
+/// parity error.
+/// Copy as much of `data` as fits into `out`, and return the full length of
+/// `data`.
+fn copy_truncated(data: &[u8], mut out: FfiBuffer) -> ApiResult<usize> {
+	let out = out.as_mut_slice().unwrap_or(&mut []);
+	let len = out.len().min(data.len());
+	out[0..len].copy_from_slice(&data[0..len]);
+	ApiResult::Ok(data.len())
+}
+
+pub(crate) fn inject_parity_error(device_id: u8) {
+	with_state(|state| {
+		if let Some((_byte, status)) = state.serial_rx[usize::from(device_id)].back_mut() {
+			*status = status.set_parity_error();
+		}
+	})
+}
+
+/// Pretend the system has changed temperature, raising the thermal
+/// interrupt if the thermal state has changed.
+pub(crate) fn set_thermal_state(thermal: ThermalState) {
+	with_state(|state| {
+		if state.thermal != thermal {
+			state.thermal = thermal;
+			state.interrupts |= 1 << THERMAL_INTERRUPT_BIT;
+		}
+	})
+}
+
+extern "C" fn api_version_get() -> Version {
+	crate::API_VERSION
+}
+
+extern "C" fn bios_version_get() -> FfiString<'static> {
+	FfiString::new("Stub BIOS\0")
+}
+
+extern "C" fn api_layout_hash() -> u32 {
+	Api::layout_hash()
+}
+
+extern "C" fn serial_get_info(device_id: u8) -> FfiOption<serial::DeviceInfo> {
+	match SERIAL_PORTS.get(usize::from(device_id)) {
+		Some(&(name, device_type, min_data_rate_bps, max_data_rate_bps)) => {
+			FfiOption::Some(serial::DeviceInfo {
+				name: FfiString::new(name),
+				device_type: device_type.make_ffi_safe(),
+				min_data_rate_bps,
+				max_data_rate_bps,
+				fixed_data_rates: FfiOption::None,
+			})
+		}
+		None => FfiOption::None,
+	}
+}
+
+extern "C" fn serial_configure(device_id: u8, config: serial::Config) -> ApiResult<()> {
+	match Option::<serial::DeviceInfo>::from(serial_get_info(device_id)) {
+		Some(info) if info.supports_data_rate(config.data_rate_bps) => ApiResult::Ok(()),
+		Some(_) => ApiResult::Err(Error::UnsupportedConfiguration),
+		None => ApiResult::Err(Error::InvalidDevice),
+	}
+}
+
+extern "C" fn serial_write(
+	device_id: u8,
+	data: FfiByteSlice,
+	_timeout: FfiOption<Timeout>,
+) -> ApiResult<usize> {
+	with_state(
+		|state| match state.serial_rx.get_mut(usize::from(device_id)) {
+			Some(rx) => {
+				rx.extend(
+					data.as_slice()
+						.iter()
+						.map(|byte| (*byte, serial::ByteStatus::new())),
+				);
+				ApiResult::Ok(data.as_slice().len())
+			}
+			None => ApiResult::Err(Error::InvalidDevice),
+		},
+	)
+}
+
+extern "C" fn serial_read(
+	device_id: u8,
+	mut data: FfiBuffer,
+	_timeout: FfiOption<Timeout>,
+) -> ApiResult<usize> {
+	with_state(
+		|state| match state.serial_rx.get_mut(usize::from(device_id)) {
+			Some(rx) => {
+				let out = data.as_mut_slice().unwrap_or(&mut []);
+				let len = out.len().min(rx.len());
+				for (slot, (byte, _status)) in out.iter_mut().zip(rx.drain(0..len)) {
+					*slot = byte;
+				}
+				ApiResult::Ok(len)
+			}
+			None => ApiResult::Err(Error::InvalidDevice),
+		},
+	)
+}
+
+/// Every device is a loopback, so the reply is whatever was sent.
+extern "C" fn serial_transaction(
+	device_id: u8,
+	tx: FfiByteSlice,
+	rx: FfiBuffer,
+	_turnaround: FfiDuration,
+	timeout: FfiOption<Timeout>,
+) -> ApiResult<usize> {
+	match serial_write(device_id, tx, timeout.clone()) {
+		ApiResult::Ok(_) => serial_read(device_id, rx, timeout),
+		ApiResult::Err(e) => ApiResult::Err(e),
+	}
+}
+
+extern "C" fn serial_read_tagged(
+	device_id: u8,
+	mut data: FfiBuffer,
+	mut status: FfiBuffer,
+	_timeout: FfiOption<Timeout>,
+) -> ApiResult<usize> {
+	let data = data.as_mut_slice().unwrap_or(&mut []);
+	let status = status.as_mut_slice().unwrap_or(&mut []);
+	if data.len() != status.len() {
+		return ApiResult::Err(Error::UnsupportedConfiguration);
+	}
+	with_state(
+		|state| match state.serial_rx.get_mut(usize::from(device_id)) {
+			Some(rx) => {
+				let len = data.len().min(rx.len());
+				let slots = data.iter_mut().zip(status.iter_mut());
+				for ((data_slot, status_slot), (byte, byte_status)) in slots.zip(rx.drain(0..len)) {
+					*data_slot = byte;
+					*status_slot = byte_status.as_u8();
+				}
+				ApiResult::Ok(len)
+			}
+			None => ApiResult::Err(Error::InvalidDevice),
+		},
+	)
+}
+
+extern "C" fn serial_flush(device_id: u8, _timeout: FfiOption<Timeout>) -> ApiResult<()> {
+	if usize::from(device_id) < SERIAL_PORTS.len() {
+		ApiResult::Ok(())
+	} else {
+		ApiResult::Err(Error::InvalidDevice)
+	}
+}
+
+extern "C" fn time_clock_get() -> Time {
+	with_state(|state| state.clock.clone())
+}
+
+extern "C" fn time_clock_set(time: Time) {
+	with_state(|state| state.clock = time)
+}
+
+/// Time moves on by one tick every time you look at it.
+extern "C" fn time_ticks_get() -> Ticks {
+	with_state(|state| {
+		state.ticks.0 += 1;
+		state.ticks
+	})
+}
+
+extern "C" fn time_ticks_per_second() -> Ticks {
+	Ticks(1000)
+}
+
+extern "C" fn configuration_get(buffer: FfiBuffer) -> ApiResult<usize> {
+	with_state(|state| copy_truncated(&state.configuration, buffer))
+}
+
+extern "C" fn configuration_set(buffer: FfiByteSlice) -> ApiResult<()> {
+	if buffer.as_slice().len() > CONFIGURATION_MAX_LEN {
+		return ApiResult::Err(Error::UnsupportedConfiguration);
+	}
+	with_state(|state| state.configuration = buffer.as_slice().to_vec());
+	ApiResult::Ok(())
+}
+
+extern "C" fn configuration_get_max_len() -> usize {
+	CONFIGURATION_MAX_LEN
+}
+
+extern "C" fn system_name_get(buffer: FfiBuffer) -> ApiResult<usize> {
+	with_state(|state| copy_truncated(&state.system_name, buffer))
+}
+
+extern "C" fn system_name_set(name: FfiByteSlice) -> ApiResult<()> {
+	let name = name.as_slice();
+	if name.len() > crate::SYSTEM_NAME_MAX_LEN || !name.is_ascii() {
+		return ApiResult::Err(Error::UnsupportedConfiguration);
+	}
+	with_state(|state| state.system_name = name.to_vec());
+	ApiResult::Ok(())
+}
+
+extern "C" fn machine_get_unique_id(out: FfiBuffer) -> ApiResult<usize> {
+	copy_truncated(&MACHINE_ID, out)
+}
+
+/// Only 640x480 modes are supported, and the graphics modes need VRAM.
+extern "C" fn video_is_valid_mode(mode: video::Mode) -> bool {
+	mode.is_well_formed() && mode.timing() == video::Timing::T640x480
+}
+
+extern "C" fn video_mode_needs_vram(mode: video::Mode) -> bool {
+	!mode.is_text_mode()
+}
+
+unsafe extern "C" fn video_set_mode(mode: video::Mode, vram: *mut u32) -> ApiResult<()> {
+	if !video_is_valid_mode(mode) || (video_mode_needs_vram(mode) && vram.is_null()) {
+		return ApiResult::Err(Error::UnsupportedConfiguration);
+	}
+	with_state(|state| {
+		state.mode = mode;
+		state.vram = vram;
+	});
+	ApiResult::Ok(())
+}
+
+extern "C" fn video_get_mode() -> video::Mode {
+	with_state(|state| state.mode)
+}
+
+extern "C" fn video_get_framebuffer() -> *mut u32 {
+	with_state(|state| state.vram)
+}
+
+extern "C" fn video_wait_for_line(_line: u16) {}
+
+extern "C" fn video_get_palette(palette_idx: u8) -> FfiOption<video::RGBColour> {
+	with_state(|state| state.palette.get(usize::from(palette_idx)).copied().into())
+}
+
+extern "C" fn video_set_palette(palette_idx: u8, colour: video::RGBColour) {
+	with_state(|state| {
+		if let Some(entry) = state.palette.get_mut(usize::from(palette_idx)) {
+			*entry = colour;
+		}
+	})
+}
+
+unsafe extern "C" fn video_set_whole_palette(start: *const video::RGBColour, length: usize) {
+	let colours = core::slice::from_raw_parts(start, length);
+	with_state(|state| {
+		for (entry, colour) in state.palette.iter_mut().zip(colours) {
+			*entry = *colour;
+		}
+	})
+}
+
+extern "C" fn memory_get_region(region_index: u8) -> FfiOption<MemoryRegion> {
+	match MEMORY_REGIONS.get(usize::from(region_index)) {
+		Some(&(start, length, kind)) => FfiOption::Some(MemoryRegion {
+			start: start as *mut u8,
+			length,
+			kind: kind.make_ffi_safe(),
+		}),
+		None => FfiOption::None,
+	}
+}
+
+extern "C" fn i2c_bus_get_info(bus_id: u8) -> FfiOption<i2c::BusInfo> {
+	match I2C_BUSES.get(usize::from(bus_id)) {
+		Some(&(name, max_speed_hz)) => FfiOption::Some(i2c::BusInfo {
+			name: FfiString::new(name),
+			max_speed_hz,
+			supports_10bit: false,
+			supports_clock_stretching: true,
+		}),
+		None => FfiOption::None,
+	}
+}
+
+/// The EEPROM takes a register address, then writes any further bytes
+/// it is sent into consecutive registers, then reads back the registers
+/// that follow.
+extern "C" fn i2c_write_read(
+	bus_id: u8,
+	i2c_device_address: u8,
+	tx: FfiByteSlice,
+	tx2: FfiByteSlice,
+	mut rx: FfiBuffer,
+) -> ApiResult<()> {
+	if usize::from(bus_id) >= I2C_BUSES.len() {
+		return ApiResult::Err(Error::InvalidDevice);
+	}
+	let (reg, data) = match tx.as_slice().split_first() {
+		Some((reg, data)) if bus_id == 0 && i2c_device_address == EEPROM_ADDRESS => {
+			(usize::from(*reg), data)
+		}
+		_ => return ApiResult::Err(Error::DeviceError),
+	};
+	let write_len = data.len() + tx2.as_slice().len();
+	let out = rx.as_mut_slice().unwrap_or(&mut []);
+	with_state(
+		|state| match state.eeprom.get_mut(reg..reg + write_len + out.len()) {
+			Some(registers) => {
+				let (written, read) = registers.split_at_mut(write_len);
+				for (register, byte) in written.iter_mut().zip(data.iter().chain(tx2.as_slice())) {
+					*register = *byte;
+				}
+				out.copy_from_slice(read);
+				ApiResult::Ok(())
+			}
+			None => ApiResult::Err(Error::DeviceError),
+		},
+	)
+}
+
+extern "C" fn i2c_lock(bus_id: u8) -> ApiResult<()> {
+	with_state(
+		|state| match state.i2c_locked.get_mut(usize::from(bus_id)) {
+			Some(true) => ApiResult::Err(Error::Busy),
+			Some(locked) => {
+				*locked = true;
+				ApiResult::Ok(())
+			}
+			None => ApiResult::Err(Error::InvalidDevice),
+		},
+	)
+}
+
+extern "C" fn i2c_unlock(bus_id: u8) -> ApiResult<()> {
+	with_state(
+		|state| match state.i2c_locked.get_mut(usize::from(bus_id)) {
+			Some(locked) => {
+				*locked = false;
+				ApiResult::Ok(())
+			}
+			None => ApiResult::Err(Error::InvalidDevice),
+		},
+	)
+}
+
+extern "C" fn audio_mixer_channel_get_info(
+	audio_mixer_id: u8,
+) -> FfiOption<audio::MixerChannelInfo> {
+	match AUDIO_MIXER_CHANNELS.get(usize::from(audio_mixer_id)) {
+		Some(&(name, direction)) => FfiOption::Some(audio::MixerChannelInfo {
+			name: FfiString::new(name),
+			direction: direction.make_ffi_safe(),
+			max_level: 255,
+			current_level: 128,
+			muted: false,
+		}),
+		None => FfiOption::None,
+	}
+}
+
+extern "C" fn bus_select(peripheral_id: FfiOption<u8>) {
+	with_state(|state| state.bus_selected = peripheral_id.into())
+}
+
+extern "C" fn bus_get_selected() -> FfiOption<u8> {
+	with_state(|state| state.bus_selected.into())
+}
+
+extern "C" fn bus_get_info(peripheral_id: u8) -> FfiOption<bus::PeripheralInfo> {
+	match BUS_PERIPHERALS.get(usize::from(peripheral_id)) {
+		Some(&(name, kind, interrupt_bit)) => FfiOption::Some(bus::PeripheralInfo {
+			name: FfiString::new(name),
+			kind: kind.make_ffi_safe(),
+			interrupt_bit: interrupt_bit.into(),
+		}),
+		None => FfiOption::None,
+	}
+}
+
+extern "C" fn bus_interrupt_status() -> u32 {
+	with_state(|state| state.interrupts)
+}
+
+extern "C" fn block_dev_get_info(device_id: u8) -> FfiOption<block_dev::DeviceInfo> {
+	match BLOCK_DEVICES.get(usize::from(device_id)) {
+		Some(&(name, num_blocks, _id)) => FfiOption::Some(block_dev::DeviceInfo {
+			name: FfiString::new(name),
+			device_type: block_dev::DeviceType::SecureDigitalCard.make_ffi_safe(),
+			block_size: 512,
+			num_blocks,
+			ejectable: false,
+			removable: true,
+			media_present: true,
+			read_only: false,
+		}),
+		None => FfiOption::None,
+	}
+}
+
+extern "C" fn block_dev_get_id(device_id: u8, out: FfiBuffer) -> ApiResult<usize> {
+	match BLOCK_DEVICES.get(usize::from(device_id)) {
+		Some(&(_name, _num_blocks, id)) => copy_truncated(id, out),
+		None => ApiResult::Err(Error::InvalidDevice),
+	}
+}
+
+extern "C" fn thermal_get_state() -> ApiResult<FfiThermalState> {
+	with_state(|state| {
+		state.interrupts &= !(1 << THERMAL_INTERRUPT_BIT);
+		ApiResult::Ok(state.thermal.make_ffi_safe())
+	})
+}
+
+extern "C" fn thermal_get_interrupt_bit() -> FfiOption<u8> {
+	FfiOption::Some(THERMAL_INTERRUPT_BIT)
+}
+
+unused! {
+	fn serial_set_control_lines(u8, serial::ControlLines) -> ApiResult<()>;
+	fn serial_get_status_lines(u8) -> ApiResult<serial::StatusLines>;
+	fn serial_send_break(u8, u16) -> ApiResult<()>;
+	fn serial_get_rx_count(u8) -> ApiResult<usize>;
+	fn serial_get_tx_space(u8) -> ApiResult<usize>;
+	fn delay_ms(u32);
+	fn delay_us(u32);
+	fn time_alarm_set(Time) -> ApiResult<()>;
+	fn time_alarm_clear() -> ApiResult<()>;
+	fn video_cycle_palette(u8, u8, i8) -> ApiResult<()>;
+	fn video_get_palette_size() -> u16;
+	fn video_show_test_pattern(video::FfiTestPattern) -> ApiResult<()>;
+	fn video_get_supported_mode(u8) -> FfiOption<video::Mode>;
+	fn video_set_cursor(u16, u16, bool) -> ApiResult<()>;
+	fn video_set_cursor_style(video::FfiCursorStyle) -> ApiResult<()>;
+	fn video_reset_font() -> ApiResult<()>;
+	fn video_flip_framebuffer(u8) -> ApiResult<()>;
+	fn video_get_blink_phase() -> bool;
+	fn video_fill_rect(u16, u16, u16, u16, u32) -> ApiResult<()>;
+	fn video_set_line_interrupt(u16, bool) -> ApiResult<()>;
+	fn hid_get_event() -> ApiResult<FfiOption<hid::HidEvent>>;
+	fn hid_set_leds(hid::KeyboardLeds) -> ApiResult<()>;
+	fn hid_peek_event() -> ApiResult<FfiOption<hid::HidEvent>>;
+	fn hid_get_modifiers() -> hid::Modifiers;
+	fn hid_get_device_info(u8) -> FfiOption<hid::DeviceInfo>;
+	fn hid_set_repeat_config(u16, u16) -> ApiResult<()>;
+	fn hid_set_raw_mode(bool) -> ApiResult<()>;
+	fn i2c_bus_scan(u8, FfiBuffer) -> ApiResult<usize>;
+	fn i2c_bus_set_speed(u8, u32) -> ApiResult<u32>;
+	fn i2c_bus_get_speed(u8) -> ApiResult<u32>;
+	fn audio_mixer_channel_set_level(u8, u8) -> ApiResult<()>;
+	fn audio_output_set_config(audio::Config) -> ApiResult<()>;
+	fn audio_output_get_config() -> ApiResult<audio::Config>;
+	fn audio_output_get_space() -> ApiResult<usize>;
+	fn audio_input_set_config(audio::Config) -> ApiResult<()>;
+	fn audio_input_get_config() -> ApiResult<audio::Config>;
+	fn audio_input_get_count() -> ApiResult<usize>;
+	fn audio_set_monitor(bool, u8) -> ApiResult<()>;
+	fn audio_play_tone(u16, u16, u8) -> ApiResult<()>;
+	fn audio_stop_tone(u8) -> ApiResult<()>;
+	fn audio_output_get_latency() -> ApiResult<u32>;
+	fn audio_mixer_channel_set_mute(u8, bool) -> ApiResult<()>;
+	fn audio_mixer_channel_get_level(u8) -> ApiResult<u8>;
+	fn audio_output_set_enabled(bool) -> ApiResult<()>;
+	fn audio_input_set_enabled(bool) -> ApiResult<()>;
+	fn audio_output_query_config(audio::Config) -> ApiResult<audio::Config>;
+	fn audio_input_query_config(audio::Config) -> ApiResult<audio::Config>;
+	fn bus_write_read(FfiByteSlice, FfiByteSlice, FfiBuffer) -> ApiResult<()>;
+	fn bus_exchange(FfiBuffer) -> ApiResult<()>;
+	fn bus_configure(u32, bus::FfiSpiMode) -> ApiResult<u32>;
+	fn bus_transfer(FfiByteSlice, FfiBuffer) -> ApiResult<()>;
+	fn bus_set_interrupt_mask(u32);
+	fn bus_get_interrupt_mask() -> u32;
+	fn bus_interrupt_status_masked() -> u32;
+	fn block_dev_eject(u8) -> ApiResult<()>;
+	fn block_write(u8, block_dev::BlockIdx, u8, FfiByteSlice) -> ApiResult<()>;
+	fn block_read(u8, block_dev::BlockIdx, u8, FfiBuffer) -> ApiResult<()>;
+	fn block_verify(u8, block_dev::BlockIdx, u8, FfiByteSlice) -> ApiResult<()>;
+	fn block_dev_flush(u8) -> ApiResult<()>;
+	fn block_dev_discard(u8, block_dev::BlockIdx, u32) -> ApiResult<()>;
+	fn block_dev_read_toc(u8, FfiBuffer) -> ApiResult<usize>;
+	fn block_dev_set_timeout(u8, FfiOption<Timeout>) -> ApiResult<()>;
+	fn power_idle();
+	fn power_control(FfiPowerMode) -> !;
+	fn power_can_poweroff() -> bool;
+	fn power_get_status() -> FfiOption<PowerStatus>;
+	fn cpu_get_frequency_hz() -> u32;
+	fn cpu_set_performance(FfiPerformanceLevel) -> ApiResult<u32>;
+	fn watchdog_configure(u32) -> ApiResult<u32>;
+	fn watchdog_feed();
+	fn watchdog_disable() -> ApiResult<()>;
+	fn compare_and_swap_bool(&core::sync::atomic::AtomicBool, bool, bool) -> bool;
+	fn firmware_update_begin(usize) -> ApiResult<()>;
+	fn firmware_update_write(usize, FfiByteSlice) -> ApiResult<()>;
+	fn firmware_update_finish(u32) -> ApiResult<()>;
+	fn firmware_region() -> FfiOption<MemoryRegion>;
+	fn firmware_erase(u32, u32) -> ApiResult<()>;
+	fn random_get(FfiBuffer) -> ApiResult<usize>;
+	fn random_is_secure() -> bool;
+}
+
+unused! {
+	unsafe fn video_set_font(FfiByteSlice, u8) -> ApiResult<()>;
+	unsafe fn video_register_framebuffer(*const u8) -> ApiResult<u8>;
+	unsafe fn video_get_whole_palette(*mut video::RGBColour, usize) -> usize;
+	unsafe fn video_blit(*const u8, usize, u16, u16, u16, u16) -> ApiResult<()>;
+	unsafe fn audio_output_data(FfiByteSlice) -> ApiResult<usize>;
+	unsafe fn audio_input_data(FfiBuffer) -> ApiResult<usize>;
+	unsafe fn firmware_write(u32, FfiByteSlice) -> ApiResult<()>;
+}
+
+// ============================================================================
+// End of File
+// ============================================================================
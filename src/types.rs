@@ -46,6 +46,8 @@ pub type OsStartFn = extern "C" fn(&crate::Api) -> !;
 /// Error>`.
 #[repr(u8)]
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Error {
 	/// An invalid device number was given to the function.
 	InvalidDevice = 1,
@@ -61,21 +63,51 @@ pub enum Error {
 	NoMediaFound,
 	/// You used a Block Device API asked for a block the device doesn't have
 	BlockOutOfBounds,
+	/// The device (or bus) is already in use
+	Busy,
+	/// The operation did not complete before the timeout expired
+	Timeout,
+	/// The operation cannot complete right now, and you asked not to wait
+	WouldBlock,
+	/// The function is implemented, but this particular device cannot do
+	/// what was asked (unlike `Unimplemented`, where the function does
+	/// nothing for any device)
+	NotSupported,
 }
 
 /// An error that specifically means 'unable to convert integer to enum'
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct EnumConversionFail();
 
 /// Describes a period of time, after which the BIOS should give up.
 #[repr(C)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Timeout(u32);
 
+/// Describes a period of time, with nanosecond resolution.
+///
+/// Use this instead of [`Timeout`] in the places where millisecond
+/// granularity is too coarse (e.g. the turnaround in
+/// [`Api::serial_transaction`](crate::Api::serial_transaction)). The
+/// nanoseconds portion is always kept below one second.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FfiDuration {
+	secs: u32,
+	nanos: u32,
+}
+
 /// Represents an instant in time between 2000-01-01T00:00:00Z and
 /// 2136-02-07T06:28:16Z.
 #[repr(C)]
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Time {
 	/// Seconds since the epoch
 	pub secs: u32,
@@ -87,7 +119,9 @@ pub struct Time {
 ///
 /// Usually runs at 1 kHz.
 #[repr(C)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ticks(pub u64);
 
 make_ffi_enum!("The kinds of memory we know about",
@@ -117,6 +151,7 @@ make_ffi_enum!("The kinds of memory we know about",
 /// Represents a region in memory.
 #[repr(C)]
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct MemoryRegion {
 	/// The address the region starts at
 	pub start: *mut u8,
@@ -140,6 +175,46 @@ make_ffi_enum!("The kinds of power control we can do.",
 	Bootloader
 });
 
+/// Describes where the system is getting its power from.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PowerStatus {
+	/// Is the system running from an external power source (e.g. mains, or
+	/// USB)?
+	pub on_external_power: bool,
+	/// Is there a battery fitted?
+	pub battery_present: bool,
+	/// How full is the battery, from 0 to 100 percent?
+	///
+	/// Set to [`PowerStatus::UNKNOWN_PERCENT`] if there is no battery, or its
+	/// level cannot be measured.
+	pub battery_percent: u8,
+}
+
+make_ffi_enum!("The thermal state of the system.",
+	ThermalState, FfiThermalState, {
+	#[doc = "Operating within normal temperature limits"]
+	Normal,
+	#[doc = "Running warm, but performance is not yet affected"]
+	Warning,
+	#[doc = "Running hot, and the clock has been reduced to shed heat"]
+	Throttling,
+	#[doc = "Dangerously hot. The OS should reduce load, or shut down, immediately."]
+	Critical
+});
+
+make_ffi_enum!("How the BIOS should trade off speed against power consumption.",
+	PerformanceLevel, FfiPerformanceLevel, {
+	#[doc = "Run as slowly as possible, to save power"]
+	PowerSave,
+	#[doc = "A sensible default for general use"]
+	Balanced,
+	#[doc = "Run as fast as possible, whatever the power cost"]
+	Performance
+});
+
 // ============================================================================
 // Impls
 // ============================================================================
@@ -149,9 +224,22 @@ make_ffi_enum!("The kinds of power control we can do.",
 // Timeout
 
 impl Timeout {
+	/// A timeout which never expires - that is, wait forever.
+	///
+	/// This is distinct from `Timeout::new_ms(0)`, which means don't wait at
+	/// all.
+	pub const UNBOUNDED: Timeout = Timeout(u32::MAX);
+
+	/// The longest timeout which still expires, just under 50 days.
+	pub const MAX: Timeout = Timeout(u32::MAX - 1);
+
 	/// Create a new timeout, in milliseconds.
+	///
+	/// Saturates at [`Timeout::MAX`], so this never gives
+	/// [`Timeout::UNBOUNDED`] - use that constant if you want to wait
+	/// forever.
 	pub fn new_ms(milliseconds: u32) -> Timeout {
-		Timeout(milliseconds)
+		Timeout(milliseconds.min(Self::MAX.0))
 	}
 
 	/// Create a new timeout, in seconds.
@@ -164,10 +252,235 @@ impl Timeout {
 	pub fn get_ms(self) -> u32 {
 		self.0
 	}
+
+	/// Is this the [`Timeout::UNBOUNDED`] timeout, which never expires?
+	pub const fn is_unbounded(self) -> bool {
+		self.0 == u32::MAX
+	}
+
+	/// Work out when this timeout expires, if it starts at `now`.
+	///
+	/// The `rate` is the number of ticks per second, as given by
+	/// `Api::time_ticks_per_second`. The deadline is rounded up to the next
+	/// whole tick, so it is never too early. An unbounded timeout gives
+	/// `Ticks(u64::MAX)`, which will never be reached.
+	pub const fn deadline_from(self, now: Ticks, rate: Ticks) -> Ticks {
+		if self.is_unbounded() {
+			return Ticks(u64::MAX);
+		}
+		let ticks = (self.0 as u128 * rate.0 as u128).div_ceil(1000);
+		if ticks > u64::MAX as u128 {
+			Ticks(u64::MAX)
+		} else {
+			Ticks(now.0.saturating_add(ticks as u64))
+		}
+	}
+}
+
+impl From<FfiDuration> for Timeout {
+	/// Convert a duration into a timeout.
+	///
+	/// Rounds up to the next whole millisecond, so the timeout is never
+	/// shorter than the duration, and saturates at [`Timeout::MAX`]. A
+	/// finite duration never gives [`Timeout::UNBOUNDED`].
+	fn from(duration: FfiDuration) -> Timeout {
+		let milliseconds = duration.as_nanos().div_ceil(1_000_000);
+		if milliseconds > Timeout::MAX.0 as u64 {
+			Timeout::MAX
+		} else {
+			Timeout(milliseconds as u32)
+		}
+	}
+}
+
+// FfiDuration
+
+impl FfiDuration {
+	const NANOS_PER_SEC: u32 = 1_000_000_000;
+
+	/// Create a new duration.
+	///
+	/// Any whole seconds in `nanos` are carried into `secs`. The seconds
+	/// value saturates at `u32::MAX`.
+	pub const fn new(secs: u32, nanos: u32) -> FfiDuration {
+		FfiDuration {
+			secs: secs.saturating_add(nanos / Self::NANOS_PER_SEC),
+			nanos: nanos % Self::NANOS_PER_SEC,
+		}
+	}
+
+	/// Create a new duration, in milliseconds.
+	pub const fn from_millis(milliseconds: u32) -> FfiDuration {
+		FfiDuration {
+			secs: milliseconds / 1_000,
+			nanos: (milliseconds % 1_000) * 1_000_000,
+		}
+	}
+
+	/// Create a new duration, in microseconds.
+	///
+	/// The seconds value saturates at `u32::MAX`.
+	pub const fn from_micros(microseconds: u64) -> FfiDuration {
+		let secs = microseconds / 1_000_000;
+		let nanos = (microseconds % 1_000_000) as u32 * 1_000;
+		if secs > u32::MAX as u64 {
+			FfiDuration {
+				secs: u32::MAX,
+				nanos,
+			}
+		} else {
+			FfiDuration {
+				secs: secs as u32,
+				nanos,
+			}
+		}
+	}
+
+	/// Get the whole seconds portion of this duration.
+	pub const fn secs(self) -> u32 {
+		self.secs
+	}
+
+	/// Get the fractional part of this duration, in nanoseconds.
+	///
+	/// This is always less than one second.
+	pub const fn subsec_nanos(self) -> u32 {
+		self.nanos
+	}
+
+	/// Get the total length of this duration, in nanoseconds.
+	pub const fn as_nanos(self) -> u64 {
+		(self.secs as u64 * Self::NANOS_PER_SEC as u64) + self.nanos as u64
+	}
+}
+
+impl From<Timeout> for FfiDuration {
+	/// Convert a timeout into a duration.
+	///
+	/// A duration cannot wait forever, so [`Timeout::UNBOUNDED`] becomes a
+	/// finite duration of `u32::MAX` milliseconds (about 49.7 days).
+	/// Converting that back gives [`Timeout::MAX`], not
+	/// [`Timeout::UNBOUNDED`]. Every other timeout survives the round trip.
+	fn from(timeout: Timeout) -> FfiDuration {
+		FfiDuration::from_millis(timeout.get_ms())
+	}
 }
 
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FfiDuration {
+	/// Durations are deserialised through [`FfiDuration::new`], so the
+	/// nanoseconds portion is kept below one second.
+	fn deserialize<D>(deserializer: D) -> Result<FfiDuration, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		#[derive(serde::Deserialize)]
+		#[serde(rename = "FfiDuration")]
+		struct Fields {
+			secs: u32,
+			nanos: u32,
+		}
+		let fields = <Fields as serde::Deserialize>::deserialize(deserializer)?;
+		Ok(FfiDuration::new(fields.secs, fields.nanos))
+	}
+}
+
+// Error
+
+impl core::fmt::Display for Error {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		write!(
+			f,
+			"{}",
+			match self {
+				Error::InvalidDevice => "invalid device number",
+				Error::Unimplemented => "function not implemented",
+				Error::DeviceError => "device error",
+				Error::UnsupportedConfiguration => "unsupported configuration",
+				Error::NoMediaFound => "no media in drive",
+				Error::BlockOutOfBounds => "block out of bounds",
+				Error::Busy => "device busy",
+				Error::Timeout => "timed out",
+				Error::WouldBlock => "operation would block",
+				Error::NotSupported => "not supported by this device",
+			}
+		)
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
 // Time
 
+impl Time {
+	const NANOS_PER_SEC: u32 = 1_000_000_000;
+
+	/// Get the time a number of seconds after this one.
+	///
+	/// Saturates at the largest time we can represent.
+	#[inline]
+	pub const fn add_secs(self, secs: u32) -> Time {
+		Time {
+			secs: self.secs.saturating_add(secs),
+			nsecs: self.nsecs,
+		}
+	}
+
+	/// Get the time a number of nanoseconds after this one.
+	///
+	/// Any whole seconds are carried into the `secs` field. Saturates at the
+	/// largest time we can represent.
+	#[inline]
+	pub const fn add_nanos(self, nanos: u64) -> Time {
+		let total_nanos = (self.nsecs as u64).saturating_add(nanos);
+		let carry_secs = total_nanos / Self::NANOS_PER_SEC as u64;
+		let nsecs = (total_nanos % Self::NANOS_PER_SEC as u64) as u32;
+		let secs = self.secs as u64 + carry_secs;
+		if secs > u32::MAX as u64 {
+			Time {
+				secs: u32::MAX,
+				nsecs: Self::NANOS_PER_SEC - 1,
+			}
+		} else {
+			Time {
+				secs: secs as u32,
+				nsecs,
+			}
+		}
+	}
+
+	/// Get the time between an earlier time and this one, as
+	/// `(seconds, nanoseconds)`.
+	///
+	/// Returns `None` if `earlier` is actually later than this time. Either
+	/// time may have `nsecs` of a second or more; the result is always
+	/// normalised, and saturates at the largest time we can represent.
+	#[inline]
+	pub const fn checked_duration_since(&self, earlier: &Time) -> Option<(u32, u32)> {
+		let this = self.total_nanos();
+		let earlier = earlier.total_nanos();
+		if this < earlier {
+			return None;
+		}
+		let delta = this - earlier;
+		let secs = delta / Self::NANOS_PER_SEC as u64;
+		if secs > u32::MAX as u64 {
+			Some((u32::MAX, Self::NANOS_PER_SEC - 1))
+		} else {
+			Some((secs as u32, (delta % Self::NANOS_PER_SEC as u64) as u32))
+		}
+	}
+
+	/// Get this time as a number of nanoseconds since the epoch.
+	///
+	/// Cannot overflow, even if `nsecs` is not less than a second.
+	#[inline]
+	const fn total_nanos(&self) -> u64 {
+		(self.secs as u64 * Self::NANOS_PER_SEC as u64) + self.nsecs as u64
+	}
+}
+
 impl core::fmt::Display for Time {
 	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::result::Result<(), core::fmt::Error> {
 		let timestamp: chrono::DateTime<chrono::Utc> = self.into();
@@ -188,6 +501,62 @@ impl From<&Time> for chrono::DateTime<chrono::Utc> {
 	}
 }
 
+// Ticks
+
+impl Ticks {
+	/// Add a number of ticks to this value.
+	///
+	/// Returns `None` if the result would overflow.
+	#[inline]
+	pub const fn checked_add(self, other: Ticks) -> Option<Ticks> {
+		match self.0.checked_add(other.0) {
+			Some(value) => Some(Ticks(value)),
+			None => None,
+		}
+	}
+
+	/// Subtract an earlier tick value from this one.
+	///
+	/// The BIOS promises that ticks never go backwards, but if `other` is
+	/// later than `self` anyway, you get `None`.
+	#[inline]
+	pub const fn checked_sub(self, other: Ticks) -> Option<Ticks> {
+		match self.0.checked_sub(other.0) {
+			Some(value) => Some(Ticks(value)),
+			None => None,
+		}
+	}
+
+	/// Subtract an earlier tick value from this one.
+	///
+	/// The BIOS promises that ticks never go backwards, but if `other` is
+	/// later than `self` anyway, you get zero.
+	#[inline]
+	pub const fn saturating_sub(self, other: Ticks) -> Ticks {
+		Ticks(self.0.saturating_sub(other.0))
+	}
+
+	/// Get how many milliseconds have passed since an earlier tick value.
+	///
+	/// The `rate` is the number of ticks per second, as given by
+	/// `Api::time_ticks_per_second`. The result is rounded down. If
+	/// `earlier` is actually later than `self`, you get zero.
+	///
+	/// # Panics
+	///
+	/// Panics if `rate` is zero.
+	#[inline]
+	pub const fn elapsed_ms(self, earlier: Ticks, rate: Ticks) -> u64 {
+		let ticks = self.saturating_sub(earlier).0 as u128;
+		let ms = (ticks * 1000) / rate.0 as u128;
+		if ms > u64::MAX as u128 {
+			u64::MAX
+		} else {
+			ms as u64
+		}
+	}
+}
+
 // MemoryKind
 
 impl core::fmt::Display for MemoryKind {
@@ -208,6 +577,35 @@ impl core::fmt::Display for MemoryKind {
 
 // MemoryRegion
 
+impl MemoryRegion {
+	/// Get the address just past the end of this region.
+	#[inline]
+	pub fn end(&self) -> *mut u8 {
+		self.start.wrapping_add(self.length)
+	}
+
+	/// Is the given address inside this region?
+	#[inline]
+	pub fn contains(&self, addr: *const u8) -> bool {
+		let addr = addr as usize;
+		let start = self.start as usize;
+		addr >= start && (addr - start) < self.length
+	}
+
+	/// Get this region as a slice of bytes.
+	///
+	/// # Safety
+	///
+	/// The region must describe memory which is valid to read and write, and
+	/// nothing else may access that memory whilst the returned slice exists.
+	/// The lifetime of the slice is not tied to this value, so it is up to
+	/// you to make sure it is not used for too long.
+	#[inline]
+	pub unsafe fn as_mut_slice<'a>(&self) -> &'a mut [u8] {
+		core::slice::from_raw_parts_mut(self.start, self.length)
+	}
+}
+
 impl core::fmt::Display for MemoryRegion {
 	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
 		write!(
@@ -216,11 +614,308 @@ impl core::fmt::Display for MemoryRegion {
 			self.length / 1024,
 			self.kind.make_safe().unwrap_or(MemoryKind::Reserved),
 			self.start,
-			unsafe { self.start.add(self.length) },
+			self.end(),
 		)
 	}
 }
 
+// PowerStatus
+
+impl PowerStatus {
+	/// The value of `battery_percent` when the battery level is not known.
+	pub const UNKNOWN_PERCENT: u8 = 255;
+
+	/// Get the battery level, in percent, if it is known.
+	pub const fn battery_level(&self) -> Option<u8> {
+		if self.battery_present && self.battery_percent <= 100 {
+			Some(self.battery_percent)
+		} else {
+			None
+		}
+	}
+}
+
+impl core::fmt::Display for PowerStatus {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		let source = if self.on_external_power {
+			"External power"
+		} else {
+			"Battery power"
+		};
+		match (self.battery_present, self.battery_level()) {
+			(false, _) => write!(f, "{}, no battery", source),
+			(true, None) => write!(f, "{}, battery level unknown", source),
+			(true, Some(percent)) => write!(f, "{}, battery {}%", source, percent),
+		}
+	}
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn thermal_state_round_trip() {
+		for state in [
+			ThermalState::Normal,
+			ThermalState::Warning,
+			ThermalState::Throttling,
+			ThermalState::Critical,
+		] {
+			assert_eq!(state.make_ffi_safe().make_safe().unwrap(), state);
+		}
+		assert_eq!(
+			FfiThermalState(0).make_safe().unwrap(),
+			ThermalState::Normal
+		);
+		assert_eq!(
+			FfiThermalState(1).make_safe().unwrap(),
+			ThermalState::Warning
+		);
+		assert_eq!(
+			FfiThermalState(2).make_safe().unwrap(),
+			ThermalState::Throttling
+		);
+		assert_eq!(
+			FfiThermalState(3).make_safe().unwrap(),
+			ThermalState::Critical
+		);
+		assert!(FfiThermalState(4).make_safe().is_err());
+	}
+
+	#[test]
+	fn performance_level_round_trip() {
+		for level in [
+			PerformanceLevel::PowerSave,
+			PerformanceLevel::Balanced,
+			PerformanceLevel::Performance,
+		] {
+			assert_eq!(level.make_ffi_safe().make_safe().unwrap(), level);
+		}
+		assert!(FfiPerformanceLevel(3).make_safe().is_err());
+	}
+
+	#[test]
+	fn memory_region() {
+		let mut buffer = [0u8; 16];
+		let region = MemoryRegion {
+			start: buffer.as_mut_ptr(),
+			length: buffer.len(),
+			kind: MemoryKind::Ram.make_ffi_safe(),
+		};
+		let start = buffer.as_ptr();
+		assert_eq!(region.end() as *const u8, start.wrapping_add(16));
+		assert!(!region.contains(start.wrapping_sub(1)));
+		assert!(region.contains(start));
+		assert!(region.contains(start.wrapping_add(15)));
+		assert!(!region.contains(start.wrapping_add(16)));
+		let slice = unsafe { region.as_mut_slice() };
+		slice[3] = 0xAA;
+		assert_eq!(slice.len(), 16);
+		assert_eq!(buffer[3], 0xAA);
+	}
+
+	#[test]
+	fn power_status_battery_level() {
+		let mut status = PowerStatus {
+			on_external_power: false,
+			battery_present: true,
+			battery_percent: 42,
+		};
+		assert_eq!(status.battery_level(), Some(42));
+		status.battery_percent = PowerStatus::UNKNOWN_PERCENT;
+		assert_eq!(status.battery_level(), None);
+		status.battery_percent = 100;
+		status.battery_present = false;
+		assert_eq!(status.battery_level(), None);
+	}
+
+	#[test]
+	fn timeout_deadline() {
+		let rate = Ticks(1000);
+		let now = Ticks(5000);
+		assert_eq!(Timeout::new_ms(0).deadline_from(now, rate), Ticks(5000));
+		assert_eq!(Timeout::new_ms(500).deadline_from(now, rate), Ticks(5500));
+		assert_eq!(Timeout::new_secs(2).deadline_from(now, rate), Ticks(7000));
+		// 1 ms at 32.768 kHz is 32.768 ticks, which rounds up
+		let rate = Ticks(32768);
+		assert_eq!(Timeout::new_ms(1).deadline_from(now, rate), Ticks(5033));
+		assert_eq!(
+			Timeout::new_ms(1000).deadline_from(now, rate),
+			Ticks(5000 + 32768)
+		);
+		// Saturates near the end of time
+		let now = Ticks(u64::MAX - 10);
+		assert_eq!(
+			Timeout::new_ms(1000).deadline_from(now, rate),
+			Ticks(u64::MAX)
+		);
+	}
+
+	#[test]
+	fn timeout_unbounded() {
+		assert!(Timeout::UNBOUNDED.is_unbounded());
+		assert!(!Timeout::new_ms(0).is_unbounded());
+		assert!(!Timeout::new_ms(u32::MAX - 1).is_unbounded());
+		assert!(!Timeout::new_ms(u32::MAX).is_unbounded());
+		assert_eq!(Timeout::new_ms(u32::MAX), Timeout::MAX);
+		assert!(!Timeout::MAX.is_unbounded());
+		assert_eq!(
+			Timeout::UNBOUNDED.deadline_from(Ticks(0), Ticks(1000)),
+			Ticks(u64::MAX)
+		);
+	}
+
+	#[test]
+	fn ticks_sub() {
+		assert_eq!(Ticks(1500).checked_sub(Ticks(500)), Some(Ticks(1000)));
+		assert_eq!(Ticks(1500).saturating_sub(Ticks(500)), Ticks(1000));
+		assert_eq!(Ticks(500).checked_sub(Ticks(500)), Some(Ticks(0)));
+		assert_eq!(Ticks(500).saturating_sub(Ticks(500)), Ticks(0));
+		assert_eq!(Ticks(500).checked_sub(Ticks(1500)), None);
+		assert_eq!(Ticks(500).saturating_sub(Ticks(1500)), Ticks(0));
+	}
+
+	#[test]
+	fn time_add() {
+		let time = Time {
+			secs: 100,
+			nsecs: 900_000_000,
+		};
+		let later = time.clone().add_secs(60);
+		assert_eq!((later.secs, later.nsecs), (160, 900_000_000));
+		// Roll over a second boundary
+		let later = time.clone().add_nanos(200_000_000);
+		assert_eq!((later.secs, later.nsecs), (101, 100_000_000));
+		let later = time.clone().add_nanos(2_100_000_000);
+		assert_eq!((later.secs, later.nsecs), (103, 0));
+		// Saturate at the end of time
+		let later = time.clone().add_secs(u32::MAX);
+		assert_eq!((later.secs, later.nsecs), (u32::MAX, 900_000_000));
+		let later = time.add_nanos(u64::MAX);
+		assert_eq!((later.secs, later.nsecs), (u32::MAX, 999_999_999));
+	}
+
+	#[test]
+	fn time_duration_since() {
+		let earlier = Time {
+			secs: 100,
+			nsecs: 900_000_000,
+		};
+		let later = Time {
+			secs: 102,
+			nsecs: 100_000_000,
+		};
+		assert_eq!(
+			later.checked_duration_since(&earlier),
+			Some((1, 200_000_000))
+		);
+		assert_eq!(earlier.checked_duration_since(&earlier), Some((0, 0)));
+		assert_eq!(earlier.checked_duration_since(&later), None);
+		let same_sec = Time {
+			secs: 100,
+			nsecs: 800_000_000,
+		};
+		assert_eq!(same_sec.checked_duration_since(&earlier), None);
+		assert_eq!(
+			earlier.checked_duration_since(&same_sec),
+			Some((0, 100_000_000))
+		);
+		// Un-normalised `nsecs` must not overflow when borrowing a second
+		let unnormalised = Time {
+			secs: 101,
+			nsecs: u32::MAX,
+		};
+		assert_eq!(
+			unnormalised.checked_duration_since(&earlier),
+			Some((4, 394_967_295))
+		);
+		assert_eq!(earlier.checked_duration_since(&unnormalised), None);
+		let huge = Time {
+			secs: u32::MAX,
+			nsecs: u32::MAX,
+		};
+		assert_eq!(
+			huge.checked_duration_since(&Time { secs: 0, nsecs: 0 }),
+			Some((u32::MAX, 999_999_999))
+		);
+	}
+
+	#[test]
+	fn ticks_add() {
+		assert_eq!(Ticks(1500).checked_add(Ticks(500)), Some(Ticks(2000)));
+		assert_eq!(Ticks(u64::MAX).checked_add(Ticks(0)), Some(Ticks(u64::MAX)));
+		assert_eq!(Ticks(u64::MAX).checked_add(Ticks(1)), None);
+	}
+
+	#[test]
+	fn ticks_elapsed_ms() {
+		let rate = Ticks(1000);
+		assert_eq!(Ticks(1500).elapsed_ms(Ticks(1000), rate), 500);
+		assert_eq!(Ticks(1000).elapsed_ms(Ticks(1500), rate), 0);
+		// A 32.768 kHz tick rounds down
+		let rate = Ticks(32768);
+		assert_eq!(Ticks(32768).elapsed_ms(Ticks(0), rate), 1000);
+		assert_eq!(Ticks(33).elapsed_ms(Ticks(0), rate), 1);
+		assert_eq!(Ticks(32).elapsed_ms(Ticks(0), rate), 0);
+		// No overflow at the top of the range
+		assert_eq!(Ticks(u64::MAX).elapsed_ms(Ticks(0), Ticks(1000)), u64::MAX);
+		assert_eq!(
+			Ticks(u64::MAX).elapsed_ms(Ticks(0), Ticks(1_000_000)),
+			u64::MAX / 1000
+		);
+	}
+
+	#[test]
+	fn duration_normalised() {
+		let duration = FfiDuration::new(1, 2_500_000_000);
+		assert_eq!(duration.secs(), 3);
+		assert_eq!(duration.subsec_nanos(), 500_000_000);
+		assert_eq!(FfiDuration::new(u32::MAX, 1_000_000_000).secs(), u32::MAX);
+	}
+
+	#[test]
+	fn duration_from_micros() {
+		let duration = FfiDuration::from_micros(1_000_250);
+		assert_eq!(duration.secs(), 1);
+		assert_eq!(duration.subsec_nanos(), 250_000);
+		assert_eq!(duration.as_nanos(), 1_000_250_000);
+	}
+
+	#[test]
+	fn duration_timeout_round_trip() {
+		for ms in [0, 1, 999, 1_000, 1_001, 65_535_000, u32::MAX - 1] {
+			let timeout = Timeout::new_ms(ms);
+			let duration: FfiDuration = timeout.into();
+			assert_eq!(duration.as_nanos(), u64::from(ms) * 1_000_000);
+			assert_eq!(Timeout::from(duration), timeout);
+		}
+	}
+
+	#[test]
+	fn duration_timeout_unbounded() {
+		let duration = FfiDuration::from(Timeout::UNBOUNDED);
+		assert_eq!(duration, FfiDuration::from_millis(u32::MAX));
+		assert_eq!(Timeout::from(duration), Timeout::MAX);
+		assert!(!Timeout::from(duration).is_unbounded());
+	}
+
+	#[test]
+	fn duration_to_timeout_rounds_up() {
+		assert_eq!(Timeout::from(FfiDuration::from_micros(1)).get_ms(), 1);
+		assert_eq!(Timeout::from(FfiDuration::from_micros(1_500)).get_ms(), 2);
+		assert_eq!(Timeout::from(FfiDuration::new(u32::MAX, 0)), Timeout::MAX);
+		assert_eq!(
+			Timeout::from(FfiDuration::from_millis(u32::MAX)),
+			Timeout::MAX
+		);
+	}
+}
+
 // ============================================================================
 // End of File
 // ============================================================================
@@ -42,6 +42,8 @@
 /// major version, and 8-bit minor version and an 8-bit patch version.
 #[repr(transparent)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Version(pub u32);
 
 // ============================================================================
@@ -68,6 +70,108 @@ impl Version {
 	pub const fn patch(&self) -> u8 {
 		self.0 as u8
 	}
+
+	/// Parse a version in `major.minor.patch` form (e.g. `0.6.1`).
+	///
+	/// Returns `None` if the string is not in that form, or if any part is
+	/// larger than 255.
+	#[allow(clippy::should_implement_trait)]
+	pub fn from_str(s: &str) -> Option<Version> {
+		let mut parts = s.split('.');
+		let mut next_part = || -> Option<u8> {
+			let part = parts.next()?;
+			if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+				return None;
+			}
+			part.parse().ok()
+		};
+		let major = next_part()?;
+		let minor = next_part()?;
+		let patch = next_part()?;
+		if parts.next().is_some() {
+			return None;
+		}
+		Some(Version::new(major, minor, patch))
+	}
+
+	/// Can something which needs the `required` version use this version?
+	///
+	/// This follows the same rules as Cargo. The major versions must match,
+	/// and this version must be the same as, or newer than, the `required`
+	/// version. Whilst the major version is `0`, the minor versions must also
+	/// match, as every minor version may contain breaking changes.
+	///
+	/// The OS can use this to check the value from `Api::api_version_get`
+	/// against the [`API_VERSION`](crate::API_VERSION) it was built with.
+	pub const fn is_compatible_with(&self, required: Version) -> bool {
+		if self.major() != required.major() {
+			false
+		} else if self.major() == 0 {
+			self.minor() == required.minor() && self.patch() >= required.patch()
+		} else {
+			self.0 >= required.0
+		}
+	}
+}
+
+impl core::fmt::Display for Version {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		write!(f, "{}.{}.{}", self.major(), self.minor(), self.patch())
+	}
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn parse() {
+		assert_eq!(Version::from_str("0.6.1"), Some(Version::new(0, 6, 1)));
+		assert_eq!(
+			Version::from_str("255.0.10"),
+			Some(Version::new(255, 0, 10))
+		);
+		assert_eq!(Version::from_str("0.6"), None);
+		assert_eq!(Version::from_str("0.6.1.2"), None);
+		assert_eq!(Version::from_str("0.6.256"), None);
+		assert_eq!(Version::from_str("0.+6.1"), None);
+		assert_eq!(Version::from_str("0..1"), None);
+		assert_eq!(Version::from_str("v0.6.1"), None);
+	}
+
+	#[test]
+	fn compatibility() {
+		let cases = [
+			// (have, required, compatible)
+			((0, 6, 1), (0, 6, 1), true),
+			((0, 6, 2), (0, 6, 1), true),
+			((0, 6, 0), (0, 6, 1), false),
+			((0, 7, 0), (0, 6, 1), false),
+			((0, 6, 1), (0, 7, 0), false),
+			((1, 2, 0), (1, 2, 0), true),
+			((1, 3, 0), (1, 2, 5), true),
+			((1, 2, 6), (1, 2, 5), true),
+			((1, 2, 4), (1, 2, 5), false),
+			((1, 1, 9), (1, 2, 0), false),
+			((2, 0, 0), (1, 2, 0), false),
+			((1, 2, 0), (2, 0, 0), false),
+		];
+		for (have, required, compatible) in cases.iter() {
+			let have = Version::new(have.0, have.1, have.2);
+			let required = Version::new(required.0, required.1, required.2);
+			assert_eq!(
+				have.is_compatible_with(required),
+				*compatible,
+				"{:?} vs {:?}",
+				have,
+				required
+			);
+		}
+	}
 }
 
 // ============================================================================
@@ -30,7 +30,47 @@ use crate::make_ffi_enum;
 // Constants
 // ============================================================================
 
-// None
+/// The Unicode equivalent of each glyph in the standard Code Page 437 font.
+#[rustfmt::skip]
+const CP437_CHARS: [char; 256] = [
+	/* 0x00 */ '\0', '☺', '☻', '♥', '♦', '♣', '♠', '•', '◘', '○', '◙', '♂', '♀', '♪', '♫', '☼',
+	/* 0x10 */ '►', '◄', '↕', '‼', '¶', '§', '▬', '↨', '↑', '↓', '→', '←', '∟', '↔', '▲', '▼',
+	/* 0x20 */ ' ', '!', '"', '#', '$', '%', '&', '\'', '(', ')', '*', '+', ',', '-', '.', '/',
+	/* 0x30 */ '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', ':', ';', '<', '=', '>', '?',
+	/* 0x40 */ '@', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O',
+	/* 0x50 */ 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '[', '\\', ']', '^', '_',
+	/* 0x60 */ '`', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o',
+	/* 0x70 */ 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', '{', '|', '}', '~', '⌂',
+	/* 0x80 */ 'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å',
+	/* 0x90 */ 'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ',
+	/* 0xA0 */ 'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»',
+	/* 0xB0 */ '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐',
+	/* 0xC0 */ '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧',
+	/* 0xD0 */ '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀',
+	/* 0xE0 */ 'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩',
+	/* 0xF0 */ '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{a0}',
+];
+
+/// The name and colour of each of the sixteen standard VGA text colours, in
+/// palette order.
+const TEXT_COLOURS: [(&str, RGBColour); 16] = [
+	("Black", RGBColour::from_rgb(0x00, 0x00, 0x00)),
+	("Blue", RGBColour::from_rgb(0x00, 0x00, 0xAA)),
+	("Green", RGBColour::from_rgb(0x00, 0xAA, 0x00)),
+	("Cyan", RGBColour::from_rgb(0x00, 0xAA, 0xAA)),
+	("Red", RGBColour::from_rgb(0xAA, 0x00, 0x00)),
+	("Magenta", RGBColour::from_rgb(0xAA, 0x00, 0xAA)),
+	("Brown", RGBColour::from_rgb(0xAA, 0x55, 0x00)),
+	("LightGray", RGBColour::from_rgb(0xAA, 0xAA, 0xAA)),
+	("DarkGray", RGBColour::from_rgb(0x55, 0x55, 0x55)),
+	("LightBlue", RGBColour::from_rgb(0x55, 0x55, 0xFF)),
+	("LightGreen", RGBColour::from_rgb(0x55, 0xFF, 0x55)),
+	("LightCyan", RGBColour::from_rgb(0x55, 0xFF, 0xFF)),
+	("LightRed", RGBColour::from_rgb(0xFF, 0x55, 0x55)),
+	("Pink", RGBColour::from_rgb(0xFF, 0x55, 0xFF)),
+	("Yellow", RGBColour::from_rgb(0xFF, 0xFF, 0x55)),
+	("White", RGBColour::from_rgb(0xFF, 0xFF, 0xFF)),
+];
 
 // ============================================================================
 // Types
@@ -42,6 +82,7 @@ use crate::make_ffi_enum;
 /// an instance of this type.
 #[repr(transparent)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Mode(u8);
 
 make_ffi_enum!("Describes the format of the video memory.",
@@ -109,6 +150,8 @@ make_ffi_enum!("Describes the format of the video memory.",
 /// Describes the timing of the video signal.
 #[repr(C)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Timing {
 	/// VGA Standard 640x480 @ 60Hz.
 	///
@@ -125,11 +168,23 @@ pub enum Timing {
 	/// Has a 40.000 MHz pixel clock and a 37.9 kHz horizontal scan rate - but
 	/// a specific implementation may differ.
 	T800x600 = 2,
+	/// VESA Standard 1024x768 @ 60Hz.
+	///
+	/// Has a 65.000 MHz pixel clock and a 48.4 kHz horizontal scan rate - but
+	/// a specific implementation may differ.
+	T1024x768 = 3,
+	/// VESA Standard 1280x1024 @ 60Hz.
+	///
+	/// Has a 108.000 MHz pixel clock and a 64.0 kHz horizontal scan rate - but
+	/// a specific implementation may differ.
+	T1280x1024 = 4,
 }
 
 /// Describes how a video mode is caled
 #[repr(C)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Scaling {
 	/// No video scaling
 	None,
@@ -144,11 +199,15 @@ pub enum Scaling {
 /// Describes an RGB colour-triple.
 #[repr(C)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RGBColour(u32);
 
 /// Represents a glyph in the current font.
 #[repr(transparent)]
 #[derive(Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Glyph(pub u8);
 
 make_ffi_enum!("Text-mode foreground colour value.",
@@ -207,9 +266,35 @@ make_ffi_enum!("Text-mode background colour value.",
 	LightGray
 });
 
+make_ffi_enum!("A test pattern the BIOS can display instead of the framebuffer.",
+	TestPattern, FfiTestPattern, {
+	#[doc = "No test pattern - show the framebuffer as normal."]
+	Off,
+	#[doc = "Vertical bars of colour."]
+	ColourBars,
+	#[doc = "A grid of thin lines, for adjusting monitor geometry."]
+	Grid,
+	#[doc = "A smooth ramp from black to white."]
+	Gradient,
+	#[doc = "Alternating black and white squares."]
+	Checkerboard
+});
+
+make_ffi_enum!("The shape of the hardware text cursor.",
+	CursorStyle, FfiCursorStyle, {
+	#[doc = "The cursor fills the whole character cell."]
+	Block,
+	#[doc = "The cursor is a line along the bottom of the character cell."]
+	Underline,
+	#[doc = "The cursor is never drawn."]
+	None
+});
+
 /// Represents VGA format foreground/background attributes.
 #[repr(transparent)]
 #[derive(Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Attr(pub u8);
 
 /// Represents a glyph/attribute pair.
@@ -218,6 +303,8 @@ pub struct Attr(pub u8);
 /// way as IBM PC VGA.
 #[repr(transparent)]
 #[derive(Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GlyphAttr(pub u16);
 
 // ============================================================================
@@ -253,6 +340,28 @@ impl Mode {
 		Mode(mode)
 	}
 
+	/// Create a new video mode, checking that it is valid.
+	///
+	/// Each [`Format`] constrains the number of pixels on each line (e.g.
+	/// `Chunky1` needs a multiple of 32). Unlike [`Mode::new_with_scaling`],
+	/// this function checks that the resulting mode meets that constraint.
+	///
+	/// Note that a valid mode is not necessarily one that any given BIOS
+	/// supports - see `Api::video_is_valid_mode` for that.
+	#[inline]
+	pub const fn new_checked(
+		timing: Timing,
+		format: Format,
+		scaling: Scaling,
+	) -> Result<Mode, crate::EnumConversionFail> {
+		let mode = Self::new_with_scaling(timing, format, scaling);
+		if mode.is_well_formed() {
+			Ok(mode)
+		} else {
+			Err(crate::EnumConversionFail())
+		}
+	}
+
 	/// Create a new double-height video mode.
 	///
 	/// This will set the 'Vert 2x' bit.
@@ -293,6 +402,37 @@ impl Mode {
 		(self.0 & (1 << Self::HORIZ_2X_SHIFT)) != 0
 	}
 
+	/// Does this mode make sense?
+	///
+	/// The timing must be one we know about, and the number of pixels on
+	/// each line must meet the rule documented on each variant of
+	/// [`Format`]. For example, a `Chunky1` mode needs a multiple of 32
+	/// pixels per line, and a text mode needs an even number of characters
+	/// per line.
+	///
+	/// This doesn't say whether any particular BIOS supports the mode - see
+	/// `Api::video_is_valid_mode` for that.
+	#[inline]
+	pub const fn is_well_formed(self) -> bool {
+		if Self::try_from_u8(self.0).is_none() {
+			return false;
+		}
+		self.horizontal_pixels() % self.format().line_pixel_multiple() == 0
+	}
+
+	/// Get how this mode is scaled.
+	///
+	/// This is the `Scaling` that was passed to `Mode::new_with_scaling`.
+	#[inline]
+	pub const fn scaling(self) -> Scaling {
+		match (self.is_horiz_2x(), self.is_vert_2x()) {
+			(false, false) => Scaling::None,
+			(true, false) => Scaling::DoubleWidth,
+			(false, true) => Scaling::DoubleHeight,
+			(true, true) => Scaling::DoubleWidthAndHeight,
+		}
+	}
+
 	/// Gets how big a line is in bytes.
 	///
 	/// This could be a line of pixels or a line of characters, depending on
@@ -378,6 +518,8 @@ impl Mode {
 			0 => Timing::T640x480,
 			1 => Timing::T640x400,
 			2 => Timing::T800x600,
+			3 => Timing::T1024x768,
+			4 => Timing::T1280x1024,
 			_ => unreachable!(),
 		}
 	}
@@ -392,9 +534,13 @@ impl Mode {
 			(Timing::T640x480, false) => 640,
 			(Timing::T640x400, false) => 640,
 			(Timing::T800x600, false) => 800,
+			(Timing::T1024x768, false) => 1024,
+			(Timing::T1280x1024, false) => 1280,
 			(Timing::T640x480, true) => 320,
 			(Timing::T640x400, true) => 320,
 			(Timing::T800x600, true) => 400,
+			(Timing::T1024x768, true) => 512,
+			(Timing::T1280x1024, true) => 640,
 		}
 	}
 
@@ -408,9 +554,39 @@ impl Mode {
 			(Timing::T640x480, false) => 480,
 			(Timing::T640x400, false) => 400,
 			(Timing::T800x600, false) => 600,
+			(Timing::T1024x768, false) => 768,
+			(Timing::T1280x1024, false) => 1024,
 			(Timing::T640x480, true) => 240,
 			(Timing::T640x400, true) => 200,
 			(Timing::T800x600, true) => 300,
+			(Timing::T1024x768, true) => 384,
+			(Timing::T1280x1024, true) => 512,
+		}
+	}
+
+	/// Get the visible image size, as `(horizontal_pixels, vertical_lines)`.
+	///
+	/// This takes any double-width or double-height scaling into account.
+	#[inline]
+	pub const fn dimensions(self) -> (u16, u16) {
+		(self.horizontal_pixels(), self.vertical_lines())
+	}
+
+	/// Get how many bits of framebuffer each pixel takes up.
+	///
+	/// Text modes return `16`, which is the size of one glyph-attribute pair.
+	/// Note that in text modes this is per character cell (eight pixels
+	/// wide), not per pixel.
+	#[inline]
+	pub const fn bits_per_pixel(self) -> u8 {
+		match self.format() {
+			Format::Text8x8 | Format::Text8x16 => 16,
+			Format::Chunky32 => 32,
+			Format::Chunky16 => 16,
+			Format::Chunky8 => 8,
+			Format::Chunky4 => 4,
+			Format::Chunky2 => 2,
+			Format::Chunky1 => 1,
 		}
 	}
 
@@ -423,6 +599,8 @@ impl Mode {
 			Timing::T640x480 => 25175000,
 			Timing::T640x400 => 25175000,
 			Timing::T800x600 => 40000000,
+			Timing::T1024x768 => 65000000,
+			Timing::T1280x1024 => 108000000,
 		}
 	}
 
@@ -435,7 +613,73 @@ impl Mode {
 			Timing::T640x480 => 60,
 			Timing::T640x400 => 70,
 			Timing::T800x600 => 60,
+			Timing::T1024x768 => 60,
+			Timing::T1280x1024 => 60,
+		}
+	}
+
+	/// Decode one line of pixels from a framebuffer in this mode.
+	///
+	/// The `framebuffer` must be laid out as this mode describes (see
+	/// [`Format`]). The pixels on the given `line` are converted to colours
+	/// and written to `out`, using `palette` to look up the colours for
+	/// indexed formats (any index beyond the end of the palette gives black).
+	///
+	/// Returns the number of pixels written, which is limited by the size of
+	/// `out`. Text modes cannot be decoded without the font, so they give
+	/// zero pixels, as does a line which is not on screen or not within the
+	/// `framebuffer`.
+	pub fn decode_pixels(
+		self,
+		framebuffer: &[u8],
+		line: u16,
+		out: &mut [RGBColour],
+		palette: &[RGBColour],
+	) -> usize {
+		if self.is_text_mode() || line >= self.vertical_lines() {
+			return 0;
+		}
+		let line_size = self.line_size_bytes();
+		let start = usize::from(line) * line_size;
+		let line_bytes = match framebuffer.get(start..start + line_size) {
+			Some(bytes) => bytes,
+			None => return 0,
+		};
+		let lookup = |index: u8| {
+			palette
+				.get(usize::from(index))
+				.copied()
+				.unwrap_or(RGBColour::BLACK)
+		};
+		let bits_per_index = match self.format() {
+			Format::Chunky4 => 4,
+			Format::Chunky2 => 2,
+			_ => 1,
+		};
+		let num_pixels = usize::from(self.horizontal_pixels()).min(out.len());
+		for (x, pixel) in out.iter_mut().take(num_pixels).enumerate() {
+			*pixel = match self.format() {
+				Format::Chunky32 => {
+					let bytes = &line_bytes[x * 4..(x * 4) + 4];
+					let value = u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+					RGBColour::from_packed(value & 0x00FF_FFFF)
+				}
+				Format::Chunky16 => {
+					let value = u16::from_ne_bytes([line_bytes[x * 2], line_bytes[(x * 2) + 1]]);
+					RGBColour::from_chunky16(value)
+				}
+				Format::Chunky8 => lookup(line_bytes[x]),
+				_ => {
+					// Packed formats - the left-most pixel is in the top bits
+					let pixels_per_byte = 8 / bits_per_index;
+					let byte = line_bytes[x / pixels_per_byte];
+					let shift = 8 - (bits_per_index * ((x % pixels_per_byte) + 1));
+					let mask = (1 << bits_per_index) - 1;
+					lookup((byte >> shift) & mask)
+				}
+			};
 		}
+		num_pixels
 	}
 
 	/// Get the mode as an integer.
@@ -453,7 +697,7 @@ impl Mode {
 		// All scaling bits are valid.
 		// But some timings are not valid. So check for those.
 		match (mode_value >> Self::TIMING_SHIFT) & 0b111 {
-			0..=2 => Some(Mode(mode_value)),
+			0..=4 => Some(Mode(mode_value)),
 			_ => None,
 		}
 	}
@@ -470,6 +714,61 @@ impl Mode {
 	}
 }
 
+impl core::fmt::Display for Mode {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		write!(
+			f,
+			"{}x{} @ {}Hz, {}",
+			self.horizontal_pixels(),
+			self.vertical_lines(),
+			self.frame_rate_hz(),
+			self.format()
+		)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Mode {
+	/// Modes are serialised as their integer value (see [`Mode::as_u8`]).
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: serde::Serializer,
+	{
+		serializer.serialize_u8(self.as_u8())
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Mode {
+	/// Modes are deserialised from their integer value (see
+	/// [`Mode::try_from_u8`]).
+	fn deserialize<D>(deserializer: D) -> Result<Mode, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		let value = <u8 as serde::Deserialize>::deserialize(deserializer)?;
+		Mode::try_from_u8(value)
+			.ok_or_else(|| <D::Error as serde::de::Error>::custom("invalid video mode"))
+	}
+}
+
+impl Format {
+	/// The number of pixels on a line must be a multiple of this value.
+	///
+	/// This must match the rule documented on each variant.
+	const fn line_pixel_multiple(self) -> u16 {
+		match self {
+			// An even number of 8 pixel wide characters
+			Format::Text8x16 | Format::Text8x8 => 16,
+			Format::Chunky32 => 1,
+			Format::Chunky16 => 2,
+			Format::Chunky8 | Format::Chunky4 => 8,
+			Format::Chunky2 => 16,
+			Format::Chunky1 => 32,
+		}
+	}
+}
+
 impl core::fmt::Display for Format {
 	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
 		write!(
@@ -528,6 +827,38 @@ impl RGBColour {
 		RGBColour(colour)
 	}
 
+	/// Look up one of the sixteen standard VGA text colours by name.
+	///
+	/// The names are those of the [`TextForegroundColour`] variants (e.g.
+	/// `"LightBlue"`), but are not case-sensitive.
+	pub fn from_str_name(name: &str) -> Option<RGBColour> {
+		TEXT_COLOURS
+			.iter()
+			.find(|(colour_name, _)| colour_name.eq_ignore_ascii_case(name))
+			.map(|(_, colour)| *colour)
+	}
+
+	/// Parse a colour in `#RRGGBB` or `#RGB` form.
+	///
+	/// The short form repeats each digit, so `#F80` is the same as `#FF8800`.
+	pub fn from_hex(s: &str) -> Option<RGBColour> {
+		let digits = s.strip_prefix('#')?;
+		if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+			return None;
+		}
+		let value = u32::from_str_radix(digits, 16).ok()?;
+		match digits.len() {
+			6 => Some(RGBColour(value)),
+			3 => {
+				let red = ((value >> 8) & 0xF) as u8;
+				let green = ((value >> 4) & 0xF) as u8;
+				let blue = (value & 0xF) as u8;
+				Some(RGBColour::from_rgb(red * 0x11, green * 0x11, blue * 0x11))
+			}
+			_ => None,
+		}
+	}
+
 	/// Get the red-channel value
 	#[inline]
 	pub const fn red(self) -> u8 {
@@ -545,6 +876,84 @@ impl RGBColour {
 	pub const fn blue(self) -> u8 {
 		(self.0 & 0xFF) as u8
 	}
+
+	/// Convert to a [`Format::Chunky16`] pixel value.
+	///
+	/// The value is packed as `0bRRRRR_GGGGGG_BBBBB`, keeping only the top
+	/// five (or six, for green) bits of each channel.
+	#[inline]
+	pub const fn as_chunky16(self) -> u16 {
+		let red = (self.red() >> 3) as u16;
+		let green = (self.green() >> 2) as u16;
+		let blue = (self.blue() >> 3) as u16;
+		(red << 11) | (green << 5) | blue
+	}
+
+	/// Convert from a [`Format::Chunky16`] pixel value.
+	///
+	/// Each `0bRRRRR_GGGGGG_BBBBB` channel is expanded to 8 bits by repeating
+	/// its top bits in the new low bits, so that full-scale values stay
+	/// full-scale (e.g. `0xFFFF` gives white).
+	#[inline]
+	pub const fn from_chunky16(value: u16) -> RGBColour {
+		let red = ((value >> 11) & 0x1F) as u8;
+		let green = ((value >> 5) & 0x3F) as u8;
+		let blue = (value & 0x1F) as u8;
+		RGBColour::from_rgb(
+			(red << 3) | (red >> 2),
+			(green << 2) | (green >> 4),
+			(blue << 3) | (blue >> 2),
+		)
+	}
+
+	/// Mix this colour with another.
+	///
+	/// An `alpha` of `0` gives this colour, an `alpha` of `255` gives
+	/// `other`, and values in between give a proportional mix of the two.
+	/// Only integer maths is used, so this is fast enough for fading a
+	/// palette.
+	#[inline]
+	pub const fn blend(self, other: RGBColour, alpha: u8) -> RGBColour {
+		RGBColour::from_rgb(
+			Self::blend_channel(self.red(), other.red(), alpha),
+			Self::blend_channel(self.green(), other.green(), alpha),
+			Self::blend_channel(self.blue(), other.blue(), alpha),
+		)
+	}
+
+	/// Make this colour darker.
+	///
+	/// A `factor` of `255` leaves the colour as it is, and a `factor` of `0`
+	/// gives black.
+	#[inline]
+	pub const fn scale_brightness(self, factor: u8) -> RGBColour {
+		RGBColour::from_rgb(0, 0, 0).blend(self, factor)
+	}
+
+	/// Get the brightness of this colour, as perceived by the human eye.
+	///
+	/// This uses the ITU-R BT.601 weighting of `0.299` red, `0.587` green and
+	/// `0.114` blue, in integer maths.
+	#[inline]
+	pub const fn luminance(self) -> u8 {
+		let weighted =
+			(self.red() as u32 * 299) + (self.green() as u32 * 587) + (self.blue() as u32 * 114);
+		((weighted + 500) / 1000) as u8
+	}
+
+	/// Get the shade of grey with the same luminance as this colour.
+	#[inline]
+	pub const fn to_grey(self) -> RGBColour {
+		let grey = self.luminance();
+		RGBColour::from_rgb(grey, grey, grey)
+	}
+
+	/// Mix two channel values, rounding to the nearest result.
+	const fn blend_channel(a: u8, b: u8, alpha: u8) -> u8 {
+		let alpha = alpha as u32;
+		let mixed = (a as u32 * (255 - alpha)) + (b as u32 * alpha);
+		((mixed + 127) / 255) as u8
+	}
 }
 
 impl TextForegroundColour {
@@ -610,6 +1019,35 @@ impl TextBackgroundColour {
 }
 
 impl Attr {
+	/// Light gray text on a black background, as used by MS-DOS.
+	pub const NORMAL: Attr = Attr::new(
+		TextForegroundColour::LightGray,
+		TextBackgroundColour::Black,
+		false,
+	);
+
+	/// Bright white text on a black background.
+	pub const BRIGHT: Attr = Attr::new(
+		TextForegroundColour::White,
+		TextBackgroundColour::Black,
+		false,
+	);
+
+	/// Black text on a light gray background - the opposite of
+	/// [`Attr::NORMAL`].
+	pub const INVERSE: Attr = Attr::new(
+		TextForegroundColour::Black,
+		TextBackgroundColour::LightGray,
+		false,
+	);
+
+	/// Bright white text on a red background, for error messages.
+	pub const ERROR: Attr = Attr::new(
+		TextForegroundColour::White,
+		TextBackgroundColour::Red,
+		false,
+	);
+
 	/// Make a new Attribute Value.
 	///
 	/// This is packed according to the format for the IBM *Video Graphics Array* (VGA) standard,
@@ -683,6 +1121,38 @@ impl Attr {
 	}
 }
 
+impl Default for Attr {
+	/// The default attribute is [`Attr::NORMAL`].
+	fn default() -> Self {
+		Attr::NORMAL
+	}
+}
+
+impl Glyph {
+	/// Find the glyph which draws the given character.
+	///
+	/// The BIOS font uses Code Page 437, as found on the IBM PC. Returns `None`
+	/// if that font has no glyph for this character. The ASCII control
+	/// characters (other than NUL) have no glyph, as Code Page 437 puts
+	/// symbols like `☺` and `♪` in those positions instead.
+	pub fn from_char(c: char) -> Option<Glyph> {
+		if (' '..='~').contains(&c) {
+			return Some(Glyph(c as u8));
+		}
+		CP437_CHARS
+			.iter()
+			.position(|x| *x == c)
+			.map(|idx| Glyph(idx as u8))
+	}
+
+	/// Get the character that this glyph draws.
+	///
+	/// See [`Glyph::from_char`].
+	pub const fn to_char(self) -> char {
+		CP437_CHARS[self.0 as usize]
+	}
+}
+
 impl GlyphAttr {
 	/// Make a new glyph/attribute pair.
 	#[inline]
@@ -704,6 +1174,93 @@ impl GlyphAttr {
 	}
 }
 
+// ============================================================================
+// Functions
+// ============================================================================
+
+/// Get the standard 16-colour palette.
+///
+/// These are the colours of the [`TextForegroundColour`] variants, in the
+/// same order, as used by EGA and VGA text modes.
+pub const fn default_ega_palette() -> [RGBColour; 16] {
+	let mut palette = [RGBColour::from_rgb(0, 0, 0); 16];
+	let mut idx = 0;
+	while idx < palette.len() {
+		palette[idx] = TEXT_COLOURS[idx].1;
+		idx += 1;
+	}
+	palette
+}
+
+/// Get the standard 256-colour palette, for use in `Chunky8` modes.
+///
+/// * Entries 0 to 15 are the 16 colours from [`default_ega_palette`].
+/// * Entries 16 to 231 are a 6x6x6 colour cube, where each channel takes the
+///   values `0x00`, `0x33`, `0x66`, `0x99`, `0xCC` and `0xFF`. The entry for
+///   red level `r`, green level `g` and blue level `b` is at index
+///   `16 + (36 * r) + (6 * g) + b`.
+/// * Entries 232 to 255 are a 24-step greyscale ramp, from `0x080808` to
+///   `0xEEEEEE`, which fits between black and white without repeating them.
+pub const fn default_vga_palette() -> [RGBColour; 256] {
+	let mut palette = [RGBColour::from_rgb(0, 0, 0); 256];
+	let ega = default_ega_palette();
+	let mut idx = 0;
+	while idx < ega.len() {
+		palette[idx] = ega[idx];
+		idx += 1;
+	}
+	while idx < 232 {
+		let cube = (idx - 16) as u8;
+		let red = (cube / 36) * 0x33;
+		let green = ((cube / 6) % 6) * 0x33;
+		let blue = (cube % 6) * 0x33;
+		palette[idx] = RGBColour::from_rgb(red, green, blue);
+		idx += 1;
+	}
+	while idx < palette.len() {
+		let grey = 0x08 + ((idx - 232) as u8 * 10);
+		palette[idx] = RGBColour::from_rgb(grey, grey, grey);
+		idx += 1;
+	}
+	palette
+}
+
+/// Write some text into a text-mode framebuffer.
+///
+/// The glyphs in `text` are written, all with the attribute `attr`, along
+/// row `row` starting at column `col`. Text that would go past the end of
+/// the row is dropped rather than wrapping on to the next row. Nothing is
+/// written if the position is off the screen, if `mode` is not a text mode,
+/// or past the end of `fb`.
+///
+/// Returns how many cells were written.
+pub fn write_text(
+	fb: &mut [GlyphAttr],
+	mode: Mode,
+	col: u16,
+	row: u16,
+	text: &[Glyph],
+	attr: Attr,
+) -> usize {
+	let (width, height) = match (mode.text_width(), mode.text_height()) {
+		(Some(width), Some(height)) => (usize::from(width), usize::from(height)),
+		_ => return 0,
+	};
+	let (col, row) = (usize::from(col), usize::from(row));
+	if col >= width || row >= height {
+		return 0;
+	}
+	let start = (row * width) + col;
+	let end = (start + (width - col).min(text.len())).min(fb.len());
+	let Some(cells) = fb.get_mut(start..end) else {
+		return 0;
+	};
+	for (cell, glyph) in cells.iter_mut().zip(text) {
+		*cell = GlyphAttr::new(*glyph, attr);
+	}
+	cells.len()
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -712,12 +1269,388 @@ impl GlyphAttr {
 mod test {
 	use super::*;
 
+	#[test]
+	fn default_palettes() {
+		let ega = default_ega_palette();
+		assert_eq!(ega[0], RGBColour::from_packed(0x000000));
+		assert_eq!(ega[1], RGBColour::from_packed(0x0000AA));
+		assert_eq!(ega[6], RGBColour::from_packed(0xAA5500));
+		assert_eq!(ega[15], RGBColour::from_packed(0xFFFFFF));
+
+		let vga = default_vga_palette();
+		assert_eq!(vga[..16], ega[..]);
+		assert_eq!(vga[16], RGBColour::from_packed(0x000000));
+		assert_eq!(vga[21], RGBColour::from_packed(0x0000FF));
+		assert_eq!(vga[196], RGBColour::from_packed(0xFF0000));
+		assert_eq!(vga[231], RGBColour::from_packed(0xFFFFFF));
+		assert_eq!(vga[232], RGBColour::from_packed(0x080808));
+		assert_eq!(vga[255], RGBColour::from_packed(0xEEEEEE));
+	}
+
+	#[test]
+	fn write_text_origin() {
+		let mode = Mode::new(Timing::T640x480, Format::Text8x16);
+		let mut fb = [GlyphAttr(0); 80 * 30];
+		let attr = Attr(0x1F);
+		let text = [Glyph(b'H'), Glyph(b'i')];
+		assert_eq!(write_text(&mut fb, mode, 0, 0, &text, attr), 2);
+		assert_eq!(fb[0].0, GlyphAttr::new(Glyph(b'H'), attr).0);
+		assert_eq!(fb[1].0, GlyphAttr::new(Glyph(b'i'), attr).0);
+		assert_eq!(fb[2].0, 0);
+	}
+
+	#[test]
+	fn write_text_last_cell() {
+		let mode = Mode::new(Timing::T640x480, Format::Text8x16);
+		let mut fb = [GlyphAttr(0); 80 * 30];
+		let attr = Attr(0x07);
+		let text = [Glyph(b'X'), Glyph(b'Y')];
+		// Only the first glyph fits on the row
+		assert_eq!(write_text(&mut fb, mode, 79, 29, &text, attr), 1);
+		assert_eq!(fb[(80 * 30) - 1].0, GlyphAttr::new(Glyph(b'X'), attr).0);
+		// The row above is untouched
+		assert_eq!(write_text(&mut fb, mode, 78, 28, &text, attr), 2);
+		assert_eq!(fb[(80 * 29) - 1].0, GlyphAttr::new(Glyph(b'Y'), attr).0);
+		assert_eq!(fb[80 * 29].0, 0);
+	}
+
+	#[test]
+	fn write_text_off_the_edge() {
+		let mode = Mode::new(Timing::T640x480, Format::Text8x16);
+		let mut fb = [GlyphAttr(0); 80 * 30];
+		let text = [Glyph(b'!')];
+		assert_eq!(write_text(&mut fb, mode, 80, 0, &text, Attr(0x07)), 0);
+		assert_eq!(write_text(&mut fb, mode, 0, 30, &text, Attr(0x07)), 0);
+		assert!(fb.iter().all(|cell| cell.0 == 0));
+		// A framebuffer which is too short is not overrun
+		let mut short = [GlyphAttr(0); 4];
+		assert_eq!(
+			write_text(&mut short, mode, 2, 0, &[Glyph(b'!'); 4], Attr(0x07)),
+			2
+		);
+		// Nor is anything written in a graphics mode
+		let mode = Mode::new(Timing::T640x480, Format::Chunky8);
+		assert_eq!(write_text(&mut fb, mode, 0, 0, &text, Attr(0x07)), 0);
+	}
+
+	#[test]
+	fn attr_presets() {
+		assert_eq!(Attr::NORMAL.as_u8(), 0x07);
+		assert_eq!(Attr::BRIGHT.as_u8(), 0x0F);
+		assert_eq!(Attr::INVERSE.as_u8(), 0x70);
+		assert_eq!(Attr::ERROR.as_u8(), 0x4F);
+		assert_eq!(Attr::default().as_u8(), Attr::NORMAL.as_u8());
+	}
+
+	#[test]
+	fn glyph_chars() {
+		let cases = [
+			('A', 0x41),
+			('£', 0x9C),
+			('─', 0xC4),
+			('☺', 0x01),
+			('\0', 0x00),
+		];
+		for (c, idx) in cases.iter() {
+			assert_eq!(Glyph::from_char(*c).map(|g| g.0), Some(*idx));
+			assert_eq!(Glyph(*idx).to_char(), *c);
+		}
+		assert!(Glyph::from_char('🦀').is_none());
+		assert!(Glyph::from_char('\n').is_none());
+		for idx in 0..=255 {
+			assert_eq!(
+				Glyph::from_char(Glyph(idx).to_char()).map(|g| g.0),
+				Some(idx)
+			);
+		}
+	}
+
 	#[test]
 	fn mode_vga() {
 		let mode = Mode::new(Timing::T640x480, Format::Text8x16);
 		assert_eq!(0x00, mode.as_u8());
 	}
 
+	/// A fixed-size buffer we can `write!` into, as we have no `String`.
+	struct TextBuffer {
+		buf: [u8; 64],
+		len: usize,
+	}
+
+	impl TextBuffer {
+		fn new() -> TextBuffer {
+			TextBuffer {
+				buf: [0u8; 64],
+				len: 0,
+			}
+		}
+
+		fn as_str(&self) -> &str {
+			core::str::from_utf8(&self.buf[0..self.len]).unwrap()
+		}
+	}
+
+	impl core::fmt::Write for TextBuffer {
+		fn write_str(&mut self, s: &str) -> core::fmt::Result {
+			let bytes = s.as_bytes();
+			let space = self.buf.get_mut(self.len..self.len + bytes.len());
+			let space = space.ok_or(core::fmt::Error)?;
+			space.copy_from_slice(bytes);
+			self.len += bytes.len();
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn mode_display() {
+		use core::fmt::Write;
+		let cases = [
+			(
+				Mode::new(Timing::T640x480, Format::Text8x16),
+				"640x480 @ 60Hz, 8x16 Text",
+			),
+			(
+				Mode::new_double_height(Timing::T640x400, Format::Text8x8),
+				"640x200 @ 70Hz, 8x8 Text",
+			),
+			(
+				Mode::new_double_height_width(Timing::T800x600, Format::Chunky8),
+				"400x300 @ 60Hz, 8 bpp Indexed",
+			),
+		];
+		for (mode, expected) in cases.iter() {
+			let mut text = TextBuffer::new();
+			write!(text, "{}", mode).unwrap();
+			assert_eq!(text.as_str(), *expected);
+		}
+	}
+
+	#[test]
+	fn colour_blend() {
+		let black = RGBColour::from_rgb(0, 0, 0);
+		let white = RGBColour::from_rgb(0xFF, 0xFF, 0xFF);
+		let orange = RGBColour::from_rgb(0xFF, 0x80, 0x00);
+		assert_eq!(orange.blend(white, 0), orange);
+		assert_eq!(orange.blend(white, 255), white);
+		assert_eq!(black.blend(white, 127), RGBColour::from_packed(0x7F7F7F));
+		assert_eq!(black.blend(white, 128), RGBColour::from_packed(0x808080));
+	}
+
+	#[test]
+	fn colour_scale_brightness() {
+		let orange = RGBColour::from_rgb(0xFF, 0x80, 0x00);
+		assert_eq!(orange.scale_brightness(255), orange);
+		assert_eq!(orange.scale_brightness(0), RGBColour::from_rgb(0, 0, 0));
+		assert_eq!(
+			orange.scale_brightness(128),
+			RGBColour::from_rgb(0x80, 0x40, 0x00)
+		);
+	}
+
+	#[test]
+	fn colour_luminance() {
+		let green = RGBColour::from_rgb(0x00, 0xFF, 0x00);
+		let blue = RGBColour::from_rgb(0x00, 0x00, 0xFF);
+		assert_eq!(green.luminance(), 150);
+		assert_eq!(blue.luminance(), 29);
+		assert_eq!(RGBColour::from_rgb(0xFF, 0xFF, 0xFF).luminance(), 0xFF);
+		assert_eq!(green.to_grey(), RGBColour::from_rgb(150, 150, 150));
+		for level in 0..=255 {
+			let grey = RGBColour::from_rgb(level, level, level);
+			assert_eq!(grey.luminance(), level);
+			assert_eq!(grey.to_grey(), grey);
+		}
+	}
+
+	#[test]
+	fn chunky16_round_trip() {
+		let cases = [
+			(RGBColour::BLACK, 0x0000),
+			(RGBColour::WHITE, 0xFFFF),
+			(RGBColour::RED, 0xF800),
+			(RGBColour::GREEN, 0x07E0),
+			(RGBColour::BLUE, 0x001F),
+		];
+		for (colour, value) in cases.iter() {
+			assert_eq!(colour.as_chunky16(), *value);
+			assert_eq!(RGBColour::from_chunky16(*value), *colour);
+		}
+		// Mid-grey loses its low bits, but is stable once converted.
+		let grey = RGBColour::from_rgb(0x80, 0x80, 0x80);
+		assert_eq!(grey.as_chunky16(), 0x8410);
+		assert_eq!(
+			RGBColour::from_chunky16(0x8410),
+			RGBColour::from_rgb(0x84, 0x82, 0x84)
+		);
+		assert_eq!(RGBColour::from_chunky16(0x8410).as_chunky16(), 0x8410);
+	}
+
+	#[test]
+	fn colour_from_str() {
+		assert_eq!(RGBColour::from_str_name("white"), Some(RGBColour::WHITE));
+		assert_eq!(
+			RGBColour::from_str_name("LightBlue"),
+			Some(RGBColour::from_rgb(0x55, 0x55, 0xFF))
+		);
+		assert_eq!(RGBColour::from_str_name("Purple"), None);
+		assert_eq!(
+			RGBColour::from_hex("#ff8800"),
+			Some(RGBColour::from_rgb(0xFF, 0x88, 0x00))
+		);
+		assert_eq!(
+			RGBColour::from_hex("#f80"),
+			Some(RGBColour::from_rgb(0xFF, 0x88, 0x00))
+		);
+		assert_eq!(RGBColour::from_hex("ff8800"), None);
+		assert_eq!(RGBColour::from_hex("#ff88"), None);
+		assert_eq!(RGBColour::from_hex("#+f8"), None);
+		assert_eq!(RGBColour::from_hex("#gg8800"), None);
+	}
+
+	#[test]
+	fn mode_scaling() {
+		for scaling in [
+			Scaling::None,
+			Scaling::DoubleWidth,
+			Scaling::DoubleHeight,
+			Scaling::DoubleWidthAndHeight,
+		] {
+			let mode = Mode::new_with_scaling(Timing::T800x600, Format::Chunky4, scaling);
+			assert_eq!(mode.scaling(), scaling);
+		}
+	}
+
+	#[test]
+	fn mode_dimensions() {
+		let mode = Mode::new(Timing::T640x480, Format::Text8x16);
+		assert_eq!(mode.dimensions(), (640, 480));
+		let mode = Mode::new_double_width(Timing::T800x600, Format::Chunky8);
+		assert_eq!(mode.dimensions(), (400, 600));
+		let mode = Mode::new_double_height(Timing::T640x400, Format::Chunky1);
+		assert_eq!(mode.dimensions(), (640, 200));
+	}
+
+	#[test]
+	fn mode_bits_per_pixel() {
+		let cases = [
+			(Format::Text8x16, 16),
+			(Format::Text8x8, 16),
+			(Format::Chunky32, 32),
+			(Format::Chunky16, 16),
+			(Format::Chunky8, 8),
+			(Format::Chunky4, 4),
+			(Format::Chunky2, 2),
+			(Format::Chunky1, 1),
+		];
+		for (format, bits) in cases.iter() {
+			let mode = Mode::new(Timing::T640x480, *format);
+			assert_eq!(mode.bits_per_pixel(), *bits);
+		}
+	}
+
+	#[test]
+	fn mode_well_formed() {
+		assert!(Mode::new(Timing::T640x480, Format::Chunky1).is_well_formed());
+		assert!(Mode::new(Timing::T800x600, Format::Chunky1).is_well_formed());
+		assert!(Mode::new_double_width(Timing::T640x400, Format::Text8x8).is_well_formed());
+		// 400 pixels is not a multiple of 32
+		assert!(!Mode::new_double_width(Timing::T800x600, Format::Chunky1).is_well_formed());
+		// 400 pixels is 50 characters
+		assert!(Mode::new_double_width(Timing::T800x600, Format::Text8x16).is_well_formed());
+		// There is no timing 7
+		let mode = unsafe { Mode::from_u8(0x70) };
+		assert!(!mode.is_well_formed());
+	}
+
+	#[test]
+	fn mode_checked() {
+		// 320 pixels is a multiple of 32
+		let mode = Mode::new_checked(Timing::T640x480, Format::Chunky1, Scaling::DoubleWidth);
+		assert_eq!(
+			mode.ok(),
+			Some(Mode::new_double_width(Timing::T640x480, Format::Chunky1))
+		);
+		// 400 pixels is not a multiple of 32
+		assert!(
+			Mode::new_checked(Timing::T800x600, Format::Chunky1, Scaling::DoubleWidth).is_err()
+		);
+		// 400 pixels is a multiple of 16
+		assert!(Mode::new_checked(Timing::T800x600, Format::Chunky2, Scaling::DoubleWidth).is_ok());
+	}
+
+	#[test]
+	fn decode_chunky8() {
+		let mode = Mode::new_double_height_width(Timing::T640x480, Format::Chunky8);
+		let palette = [RGBColour::BLACK, RGBColour::RED, RGBColour::GREEN];
+		let mut framebuffer = [0u8; 640];
+		framebuffer[320..324].copy_from_slice(&[1, 2, 0, 200]);
+		let mut out = [RGBColour::WHITE; 4];
+		assert_eq!(mode.decode_pixels(&framebuffer, 1, &mut out, &palette), 4);
+		assert_eq!(
+			out,
+			[
+				RGBColour::RED,
+				RGBColour::GREEN,
+				RGBColour::BLACK,
+				RGBColour::BLACK
+			]
+		);
+		// Line 2 isn't in our framebuffer
+		assert_eq!(mode.decode_pixels(&framebuffer, 2, &mut out, &palette), 0);
+	}
+
+	#[test]
+	fn decode_chunky16() {
+		let mode = Mode::new_double_height_width(Timing::T640x480, Format::Chunky16);
+		let mut framebuffer = [0u8; 640];
+		for (chunk, value) in framebuffer
+			.chunks_exact_mut(2)
+			.zip([0xFFFFu16, 0xF800, 0x07E0, 0x001F, 0x0000])
+		{
+			chunk.copy_from_slice(&value.to_ne_bytes());
+		}
+		let mut out = [RGBColour::BLACK; 320];
+		assert_eq!(mode.decode_pixels(&framebuffer, 0, &mut out, &[]), 320);
+		assert_eq!(
+			out[0..5],
+			[
+				RGBColour::WHITE,
+				RGBColour::RED,
+				RGBColour::GREEN,
+				RGBColour::BLUE,
+				RGBColour::BLACK
+			]
+		);
+	}
+
+	#[test]
+	fn decode_chunky1() {
+		let mode = Mode::new_double_height_width(Timing::T640x480, Format::Chunky1);
+		let palette = [RGBColour::BLACK, RGBColour::WHITE];
+		let mut framebuffer = [0u8; 40];
+		framebuffer[0] = 0b1010_0001;
+		let mut out = [RGBColour::RED; 10];
+		assert_eq!(mode.decode_pixels(&framebuffer, 0, &mut out, &palette), 10);
+		assert_eq!(
+			out,
+			[
+				RGBColour::WHITE,
+				RGBColour::BLACK,
+				RGBColour::WHITE,
+				RGBColour::BLACK,
+				RGBColour::BLACK,
+				RGBColour::BLACK,
+				RGBColour::BLACK,
+				RGBColour::WHITE,
+				RGBColour::BLACK,
+				RGBColour::BLACK,
+			]
+		);
+		// Text modes can't be decoded
+		let mode = Mode::new(Timing::T640x480, Format::Text8x16);
+		assert_eq!(mode.decode_pixels(&[0u8; 4800], 0, &mut out, &palette), 0);
+	}
+
 	#[test]
 	fn mode_sizes() {
 		// These frame size numbers are taken from the Neotron Book.
@@ -819,6 +1752,70 @@ mod test {
 			Mode::new(Timing::T800x600, Format::Chunky1).frame_size_bytes(),
 			60000
 		);
+		assert_eq!(
+			Mode::new(Timing::T1024x768, Format::Text8x16).frame_size_bytes(),
+			12288
+		);
+		assert_eq!(
+			Mode::new(Timing::T1024x768, Format::Text8x8).frame_size_bytes(),
+			24576
+		);
+		assert_eq!(
+			Mode::new(Timing::T1024x768, Format::Chunky32).frame_size_bytes(),
+			3145728
+		);
+		assert_eq!(
+			Mode::new(Timing::T1024x768, Format::Chunky16).frame_size_bytes(),
+			1572864
+		);
+		assert_eq!(
+			Mode::new(Timing::T1024x768, Format::Chunky8).frame_size_bytes(),
+			786432
+		);
+		assert_eq!(
+			Mode::new(Timing::T1024x768, Format::Chunky4).frame_size_bytes(),
+			393216
+		);
+		assert_eq!(
+			Mode::new(Timing::T1024x768, Format::Chunky2).frame_size_bytes(),
+			196608
+		);
+		assert_eq!(
+			Mode::new(Timing::T1024x768, Format::Chunky1).frame_size_bytes(),
+			98304
+		);
+		assert_eq!(
+			Mode::new(Timing::T1280x1024, Format::Text8x16).frame_size_bytes(),
+			20480
+		);
+		assert_eq!(
+			Mode::new(Timing::T1280x1024, Format::Text8x8).frame_size_bytes(),
+			40960
+		);
+		assert_eq!(
+			Mode::new(Timing::T1280x1024, Format::Chunky32).frame_size_bytes(),
+			5242880
+		);
+		assert_eq!(
+			Mode::new(Timing::T1280x1024, Format::Chunky16).frame_size_bytes(),
+			2621440
+		);
+		assert_eq!(
+			Mode::new(Timing::T1280x1024, Format::Chunky8).frame_size_bytes(),
+			1310720
+		);
+		assert_eq!(
+			Mode::new(Timing::T1280x1024, Format::Chunky4).frame_size_bytes(),
+			655360
+		);
+		assert_eq!(
+			Mode::new(Timing::T1280x1024, Format::Chunky2).frame_size_bytes(),
+			327680
+		);
+		assert_eq!(
+			Mode::new(Timing::T1280x1024, Format::Chunky1).frame_size_bytes(),
+			163840
+		);
 
 		assert_eq!(
 			Mode::new_double_width(Timing::T640x480, Format::Text8x16).frame_size_bytes(),
@@ -916,6 +1913,70 @@ mod test {
 			Mode::new_double_width(Timing::T800x600, Format::Chunky1).frame_size_bytes(),
 			30000,
 		);
+		assert_eq!(
+			Mode::new_double_width(Timing::T1024x768, Format::Text8x16).frame_size_bytes(),
+			6144,
+		);
+		assert_eq!(
+			Mode::new_double_width(Timing::T1024x768, Format::Text8x8).frame_size_bytes(),
+			12288,
+		);
+		assert_eq!(
+			Mode::new_double_width(Timing::T1024x768, Format::Chunky32).frame_size_bytes(),
+			1572864,
+		);
+		assert_eq!(
+			Mode::new_double_width(Timing::T1024x768, Format::Chunky16).frame_size_bytes(),
+			786432,
+		);
+		assert_eq!(
+			Mode::new_double_width(Timing::T1024x768, Format::Chunky8).frame_size_bytes(),
+			393216,
+		);
+		assert_eq!(
+			Mode::new_double_width(Timing::T1024x768, Format::Chunky4).frame_size_bytes(),
+			196608,
+		);
+		assert_eq!(
+			Mode::new_double_width(Timing::T1024x768, Format::Chunky2).frame_size_bytes(),
+			98304,
+		);
+		assert_eq!(
+			Mode::new_double_width(Timing::T1024x768, Format::Chunky1).frame_size_bytes(),
+			49152,
+		);
+		assert_eq!(
+			Mode::new_double_width(Timing::T1280x1024, Format::Text8x16).frame_size_bytes(),
+			10240,
+		);
+		assert_eq!(
+			Mode::new_double_width(Timing::T1280x1024, Format::Text8x8).frame_size_bytes(),
+			20480,
+		);
+		assert_eq!(
+			Mode::new_double_width(Timing::T1280x1024, Format::Chunky32).frame_size_bytes(),
+			2621440,
+		);
+		assert_eq!(
+			Mode::new_double_width(Timing::T1280x1024, Format::Chunky16).frame_size_bytes(),
+			1310720,
+		);
+		assert_eq!(
+			Mode::new_double_width(Timing::T1280x1024, Format::Chunky8).frame_size_bytes(),
+			655360,
+		);
+		assert_eq!(
+			Mode::new_double_width(Timing::T1280x1024, Format::Chunky4).frame_size_bytes(),
+			327680,
+		);
+		assert_eq!(
+			Mode::new_double_width(Timing::T1280x1024, Format::Chunky2).frame_size_bytes(),
+			163840,
+		);
+		assert_eq!(
+			Mode::new_double_width(Timing::T1280x1024, Format::Chunky1).frame_size_bytes(),
+			81920,
+		);
 
 		assert_eq!(
 			Mode::new_double_height(Timing::T640x480, Format::Text8x16).frame_size_bytes(),
@@ -1013,6 +2074,70 @@ mod test {
 			Mode::new_double_height(Timing::T800x600, Format::Chunky1).frame_size_bytes(),
 			30000
 		);
+		assert_eq!(
+			Mode::new_double_height(Timing::T1024x768, Format::Text8x16).frame_size_bytes(),
+			6144
+		);
+		assert_eq!(
+			Mode::new_double_height(Timing::T1024x768, Format::Text8x8).frame_size_bytes(),
+			12288
+		);
+		assert_eq!(
+			Mode::new_double_height(Timing::T1024x768, Format::Chunky32).frame_size_bytes(),
+			1572864
+		);
+		assert_eq!(
+			Mode::new_double_height(Timing::T1024x768, Format::Chunky16).frame_size_bytes(),
+			786432
+		);
+		assert_eq!(
+			Mode::new_double_height(Timing::T1024x768, Format::Chunky8).frame_size_bytes(),
+			393216
+		);
+		assert_eq!(
+			Mode::new_double_height(Timing::T1024x768, Format::Chunky4).frame_size_bytes(),
+			196608
+		);
+		assert_eq!(
+			Mode::new_double_height(Timing::T1024x768, Format::Chunky2).frame_size_bytes(),
+			98304
+		);
+		assert_eq!(
+			Mode::new_double_height(Timing::T1024x768, Format::Chunky1).frame_size_bytes(),
+			49152
+		);
+		assert_eq!(
+			Mode::new_double_height(Timing::T1280x1024, Format::Text8x16).frame_size_bytes(),
+			10240
+		);
+		assert_eq!(
+			Mode::new_double_height(Timing::T1280x1024, Format::Text8x8).frame_size_bytes(),
+			20480
+		);
+		assert_eq!(
+			Mode::new_double_height(Timing::T1280x1024, Format::Chunky32).frame_size_bytes(),
+			2621440
+		);
+		assert_eq!(
+			Mode::new_double_height(Timing::T1280x1024, Format::Chunky16).frame_size_bytes(),
+			1310720
+		);
+		assert_eq!(
+			Mode::new_double_height(Timing::T1280x1024, Format::Chunky8).frame_size_bytes(),
+			655360
+		);
+		assert_eq!(
+			Mode::new_double_height(Timing::T1280x1024, Format::Chunky4).frame_size_bytes(),
+			327680
+		);
+		assert_eq!(
+			Mode::new_double_height(Timing::T1280x1024, Format::Chunky2).frame_size_bytes(),
+			163840
+		);
+		assert_eq!(
+			Mode::new_double_height(Timing::T1280x1024, Format::Chunky1).frame_size_bytes(),
+			81920
+		);
 
 		assert_eq!(
 			Mode::new_double_height_width(Timing::T640x480, Format::Text8x16).frame_size_bytes(),
@@ -1110,6 +2235,70 @@ mod test {
 			Mode::new_double_height_width(Timing::T800x600, Format::Chunky1).frame_size_bytes(),
 			15000
 		);
+		assert_eq!(
+			Mode::new_double_height_width(Timing::T1024x768, Format::Text8x16).frame_size_bytes(),
+			3072
+		);
+		assert_eq!(
+			Mode::new_double_height_width(Timing::T1024x768, Format::Text8x8).frame_size_bytes(),
+			6144
+		);
+		assert_eq!(
+			Mode::new_double_height_width(Timing::T1024x768, Format::Chunky32).frame_size_bytes(),
+			786432
+		);
+		assert_eq!(
+			Mode::new_double_height_width(Timing::T1024x768, Format::Chunky16).frame_size_bytes(),
+			393216
+		);
+		assert_eq!(
+			Mode::new_double_height_width(Timing::T1024x768, Format::Chunky8).frame_size_bytes(),
+			196608
+		);
+		assert_eq!(
+			Mode::new_double_height_width(Timing::T1024x768, Format::Chunky4).frame_size_bytes(),
+			98304
+		);
+		assert_eq!(
+			Mode::new_double_height_width(Timing::T1024x768, Format::Chunky2).frame_size_bytes(),
+			49152
+		);
+		assert_eq!(
+			Mode::new_double_height_width(Timing::T1024x768, Format::Chunky1).frame_size_bytes(),
+			24576
+		);
+		assert_eq!(
+			Mode::new_double_height_width(Timing::T1280x1024, Format::Text8x16).frame_size_bytes(),
+			5120
+		);
+		assert_eq!(
+			Mode::new_double_height_width(Timing::T1280x1024, Format::Text8x8).frame_size_bytes(),
+			10240
+		);
+		assert_eq!(
+			Mode::new_double_height_width(Timing::T1280x1024, Format::Chunky32).frame_size_bytes(),
+			1310720
+		);
+		assert_eq!(
+			Mode::new_double_height_width(Timing::T1280x1024, Format::Chunky16).frame_size_bytes(),
+			655360
+		);
+		assert_eq!(
+			Mode::new_double_height_width(Timing::T1280x1024, Format::Chunky8).frame_size_bytes(),
+			327680
+		);
+		assert_eq!(
+			Mode::new_double_height_width(Timing::T1280x1024, Format::Chunky4).frame_size_bytes(),
+			163840
+		);
+		assert_eq!(
+			Mode::new_double_height_width(Timing::T1280x1024, Format::Chunky2).frame_size_bytes(),
+			81920
+		);
+		assert_eq!(
+			Mode::new_double_height_width(Timing::T1280x1024, Format::Chunky1).frame_size_bytes(),
+			40960
+		);
 	}
 }
 
@@ -0,0 +1,666 @@
+//! # Wrapper
+//!
+//! Contains a safe, idiomatic wrapper around the raw BIOS [`Api`] structure.
+//!
+//! The [`Api`] structure has to use FFI-safe types like [`FfiByteSlice`] and
+//! [`FfiResult`](crate::FfiResult) in every function signature. The
+//! [`Bios`] type in this module converts to and from those types so that OS
+//! code can work with plain Rust slices, [`Option`] and [`Result`]. The raw
+//! [`Api`] is always available through [`Bios::api`] for anything not
+//! covered here.
+
+// Copyright (C) The Neotron Developers, 2019-2022
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+// ============================================================================
+// Imports
+// ============================================================================
+
+use crate::{
+	audio, block_dev, bus, i2c, serial, video, Api, Error, FfiBuffer, FfiByteSlice, MemoryKind,
+	MemoryRegion, Ticks, Time, Timeout, Version,
+};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+// None
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// A safe wrapper around a BIOS [`Api`] structure.
+///
+/// ```no_run
+/// # let api = neotron_common_bios::Api::make_dummy_api().unwrap();
+/// use neotron_common_bios::wrapper::Bios;
+/// let bios = Bios::new(&api);
+/// let _ = bios.serial_write(0, b"Hello, world!\r\n", None);
+/// ```
+#[derive(Clone, Copy)]
+pub struct Bios<'a>(&'a Api);
+
+// ============================================================================
+// Impls
+// ============================================================================
+
+impl<'a> Bios<'a> {
+	/// Wrap a BIOS [`Api`] structure.
+	pub const fn new(api: &'a Api) -> Bios<'a> {
+		Bios(api)
+	}
+
+	/// Get the raw BIOS [`Api`] structure, for functions this wrapper does
+	/// not cover.
+	pub const fn api(&self) -> &'a Api {
+		self.0
+	}
+
+	/// Get the version of the API the BIOS implements.
+	///
+	/// See [`Api::api_version_get`].
+	pub fn api_version_get(&self) -> Version {
+		(self.0.api_version_get)()
+	}
+
+	// ------------------------------------------------------------------------
+	// Serial
+	// ------------------------------------------------------------------------
+
+	/// Get information about a serial device, or `None` if there is no such
+	/// device.
+	///
+	/// See [`Api::serial_get_info`].
+	pub fn serial_get_info(&self, device_id: u8) -> Option<serial::DeviceInfo> {
+		(self.0.serial_get_info)(device_id).into()
+	}
+
+	/// Set the options for a given serial device.
+	///
+	/// See [`Api::serial_configure`].
+	pub fn serial_configure(&self, device_id: u8, config: serial::Config) -> Result<(), Error> {
+		(self.0.serial_configure)(device_id, config).into()
+	}
+
+	/// Write bytes to a serial port, returning how many were written.
+	///
+	/// See [`Api::serial_write`].
+	///
+	/// ```no_run
+	/// # let api = neotron_common_bios::Api::make_dummy_api().unwrap();
+	/// # use neotron_common_bios::{wrapper::Bios, Timeout};
+	/// let bios = Bios::new(&api);
+	/// let mut data: &[u8] = b"Hello, world!\r\n";
+	/// while !data.is_empty() {
+	///     let n = bios.serial_write(0, data, Some(Timeout::new_ms(100))).unwrap();
+	///     data = &data[n..];
+	/// }
+	/// ```
+	pub fn serial_write(
+		&self,
+		device_id: u8,
+		data: &[u8],
+		timeout: Option<Timeout>,
+	) -> Result<usize, Error> {
+		(self.0.serial_write)(device_id, FfiByteSlice::new(data), timeout.into()).into()
+	}
+
+	/// Read bytes from a serial port, returning how many were read.
+	///
+	/// Only the first `n` bytes of `data` are valid when this returns
+	/// `Ok(n)`.
+	///
+	/// See [`Api::serial_read`].
+	pub fn serial_read(
+		&self,
+		device_id: u8,
+		data: &mut [u8],
+		timeout: Option<Timeout>,
+	) -> Result<usize, Error> {
+		(self.0.serial_read)(device_id, FfiBuffer::new(data), timeout.into()).into()
+	}
+
+	/// Wait until all the data written to a serial port has been sent.
+	///
+	/// See [`Api::serial_flush`].
+	pub fn serial_flush(&self, device_id: u8, timeout: Option<Timeout>) -> Result<(), Error> {
+		(self.0.serial_flush)(device_id, timeout.into()).into()
+	}
+
+	/// Iterate through all the serial ports in the system, along with
+	/// their device IDs.
+	///
+	/// ```no_run
+	/// # let api = neotron_common_bios::Api::make_dummy_api().unwrap();
+	/// # use neotron_common_bios::wrapper::Bios;
+	/// let bios = Bios::new(&api);
+	/// for (device_id, info) in bios.serial_ports() {
+	///     let _ = bios.serial_write(device_id, info.name.as_str().as_bytes(), None);
+	/// }
+	/// ```
+	pub fn serial_ports(&self) -> impl Iterator<Item = (u8, serial::DeviceInfo)> + 'a {
+		let api = self.0;
+		enumerate(move |device_id| (api.serial_get_info)(device_id).into())
+	}
+
+	// ------------------------------------------------------------------------
+	// Time
+	// ------------------------------------------------------------------------
+
+	/// Get the current wall time.
+	///
+	/// See [`Api::time_clock_get`].
+	pub fn time_clock_get(&self) -> Time {
+		(self.0.time_clock_get)()
+	}
+
+	/// Set the current wall time.
+	///
+	/// See [`Api::time_clock_set`].
+	pub fn time_clock_set(&self, time: Time) {
+		(self.0.time_clock_set)(time)
+	}
+
+	/// Get the current monotonic system time.
+	///
+	/// See [`Api::time_ticks_get`].
+	pub fn time_ticks_get(&self) -> Ticks {
+		(self.0.time_ticks_get)()
+	}
+
+	/// Report the system tick rate, in ticks-per-second.
+	///
+	/// See [`Api::time_ticks_per_second`].
+	pub fn time_ticks_per_second(&self) -> Ticks {
+		(self.0.time_ticks_per_second)()
+	}
+
+	// ------------------------------------------------------------------------
+	// Configuration
+	// ------------------------------------------------------------------------
+
+	/// Get the configuration data block, returning its full length.
+	///
+	/// If the returned length is larger than `buffer`, only the first
+	/// `buffer.len()` bytes of the block were copied.
+	///
+	/// See [`Api::configuration_get`].
+	///
+	/// ```no_run
+	/// # let api = neotron_common_bios::Api::make_dummy_api().unwrap();
+	/// # use neotron_common_bios::wrapper::Bios;
+	/// let bios = Bios::new(&api);
+	/// let mut buffer = [0u8; 64];
+	/// let len = bios.configuration_get(&mut buffer).unwrap();
+	/// if len > buffer.len() {
+	///     // The buffer was too small, and the data has been truncated
+	/// }
+	/// ```
+	pub fn configuration_get(&self, buffer: &mut [u8]) -> Result<usize, Error> {
+		(self.0.configuration_get)(FfiBuffer::new(buffer)).into()
+	}
+
+	/// Set the configuration data block.
+	///
+	/// See [`Api::configuration_set`].
+	pub fn configuration_set(&self, data: &[u8]) -> Result<(), Error> {
+		(self.0.configuration_set)(FfiByteSlice::new(data)).into()
+	}
+
+	/// Get the size of the configuration data store, in bytes.
+	///
+	/// See [`Api::configuration_get_max_len`].
+	pub fn configuration_get_max_len(&self) -> usize {
+		(self.0.configuration_get_max_len)()
+	}
+
+	// ------------------------------------------------------------------------
+	// Devices
+	// ------------------------------------------------------------------------
+
+	/// Iterate through all the regions of memory the BIOS has given the OS,
+	/// along with their region indices.
+	///
+	/// See [`Api::memory_get_region`].
+	pub fn memory_regions(&self) -> impl Iterator<Item = (u8, MemoryRegion)> + 'a {
+		let api = self.0;
+		enumerate(move |region_index| (api.memory_get_region)(region_index).into())
+	}
+
+	/// Iterate through only the regions of memory which are RAM, and so
+	/// free for the OS to use.
+	pub fn ram_regions(&self) -> impl Iterator<Item = MemoryRegion> + 'a {
+		self.memory_regions()
+			.map(|(_region_index, region)| region)
+			.filter(|region| matches!(region.kind.make_safe(), Ok(MemoryKind::Ram)))
+	}
+
+	/// Add up the size of all the regions of memory which are RAM.
+	pub fn total_ram_bytes(&self) -> usize {
+		self.ram_regions().map(|region| region.length).sum()
+	}
+
+	/// Iterate through all the I²C buses in the system, along with their bus
+	/// IDs.
+	///
+	/// See [`Api::i2c_bus_get_info`].
+	pub fn i2c_buses(&self) -> impl Iterator<Item = (u8, i2c::BusInfo)> + 'a {
+		let api = self.0;
+		enumerate(move |bus_id| (api.i2c_bus_get_info)(bus_id).into())
+	}
+
+	/// Iterate through all the Audio Mixer Channels in the system, along
+	/// with their mixer IDs.
+	///
+	/// See [`Api::audio_mixer_channel_get_info`].
+	pub fn audio_mixer_channels(&self) -> impl Iterator<Item = (u8, audio::MixerChannelInfo)> + 'a {
+		let api = self.0;
+		enumerate(move |audio_mixer_id| (api.audio_mixer_channel_get_info)(audio_mixer_id).into())
+	}
+
+	/// Iterate through all the Neotron Bus Peripherals in the system, along
+	/// with their peripheral IDs.
+	///
+	/// See [`Api::bus_get_info`].
+	pub fn bus_peripherals(&self) -> impl Iterator<Item = (u8, bus::PeripheralInfo)> + 'a {
+		let api = self.0;
+		enumerate(move |peripheral_id| (api.bus_get_info)(peripheral_id).into())
+	}
+
+	/// Iterate through all the block devices in the system, along with
+	/// their device IDs.
+	///
+	/// See [`Api::block_dev_get_info`].
+	pub fn block_devices(&self) -> impl Iterator<Item = (u8, block_dev::DeviceInfo)> + 'a {
+		let api = self.0;
+		enumerate(move |device_id| (api.block_dev_get_info)(device_id).into())
+	}
+
+	// ------------------------------------------------------------------------
+	// I²C
+	// ------------------------------------------------------------------------
+
+	/// Read from consecutive registers in an I²C Device.
+	///
+	/// The register address `reg` is written to the device, and then
+	/// `out.len()` bytes are read back. This is how most I²C sensors and
+	/// small EEPROMs are read.
+	///
+	/// See [`Api::i2c_write_read`].
+	///
+	/// ```no_run
+	/// # let api = neotron_common_bios::Api::make_dummy_api().unwrap();
+	/// # use neotron_common_bios::wrapper::Bios;
+	/// let bios = Bios::new(&api);
+	/// // Read the seconds, minutes and hours from an RTC at address 0x68
+	/// let mut time = [0u8; 3];
+	/// bios.i2c_read_reg(0, 0x68, 0x00, &mut time)?;
+	/// # Ok::<(), neotron_common_bios::Error>(())
+	/// ```
+	pub fn i2c_read_reg(
+		&self,
+		bus_id: u8,
+		i2c_device_address: u8,
+		reg: u8,
+		out: &mut [u8],
+	) -> Result<(), Error> {
+		(self.0.i2c_write_read)(
+			bus_id,
+			i2c_device_address,
+			FfiByteSlice::new(&[reg]),
+			FfiByteSlice::empty(),
+			FfiBuffer::new(out),
+		)
+		.into()
+	}
+
+	/// Write to consecutive registers in an I²C Device.
+	///
+	/// The register address `reg` is written to the device, followed
+	/// immediately by `data`, in a single transaction.
+	///
+	/// See [`Api::i2c_write_read`].
+	pub fn i2c_write_reg(
+		&self,
+		bus_id: u8,
+		i2c_device_address: u8,
+		reg: u8,
+		data: &[u8],
+	) -> Result<(), Error> {
+		(self.0.i2c_write_read)(
+			bus_id,
+			i2c_device_address,
+			FfiByteSlice::new(&[reg]),
+			FfiByteSlice::new(data),
+			FfiBuffer::empty(),
+		)
+		.into()
+	}
+
+	// ------------------------------------------------------------------------
+	// Video
+	// ------------------------------------------------------------------------
+
+	/// Is this video mode supported by the BIOS?
+	///
+	/// See [`Api::video_is_valid_mode`].
+	pub fn video_is_valid_mode(&self, mode: video::Mode) -> bool {
+		(self.0.video_is_valid_mode)(mode)
+	}
+
+	/// Does the BIOS need the OS to supply VRAM for this video mode?
+	///
+	/// See [`Api::video_mode_needs_vram`].
+	pub fn video_mode_needs_vram(&self, mode: video::Mode) -> bool {
+		(self.0.video_mode_needs_vram)(mode)
+	}
+
+	/// Switch to a new video mode.
+	///
+	/// See [`Api::video_set_mode`].
+	///
+	/// # Safety
+	///
+	/// If `vram` is not null, it must point to a suitably aligned block of
+	/// memory large enough for `mode`, which remains valid (and is not
+	/// otherwise used) until the video mode is next changed.
+	pub unsafe fn video_set_mode(&self, mode: video::Mode, vram: *mut u32) -> Result<(), Error> {
+		(self.0.video_set_mode)(mode, vram).into()
+	}
+
+	/// Get the video mode the BIOS is currently in.
+	///
+	/// See [`Api::video_get_mode`].
+	pub fn video_get_mode(&self) -> video::Mode {
+		(self.0.video_get_mode)()
+	}
+
+	/// Get the framebuffer address.
+	///
+	/// See [`Api::video_get_framebuffer`].
+	pub fn video_get_framebuffer(&self) -> *mut u32 {
+		(self.0.video_get_framebuffer)()
+	}
+
+	/// Wait for the next occurence of the specified video scan-line.
+	///
+	/// See [`Api::video_wait_for_line`].
+	pub fn video_wait_for_line(&self, line: u16) {
+		(self.0.video_wait_for_line)(line)
+	}
+
+	/// Get an entry from the colour palette, or `None` if the index is not
+	/// valid.
+	///
+	/// See [`Api::video_get_palette`].
+	pub fn video_get_palette(&self, palette_idx: u8) -> Option<video::RGBColour> {
+		(self.0.video_get_palette)(palette_idx).into()
+	}
+
+	/// Set an entry in the colour palette.
+	///
+	/// See [`Api::video_set_palette`].
+	pub fn video_set_palette(&self, palette_idx: u8, colour: video::RGBColour) {
+		(self.0.video_set_palette)(palette_idx, colour)
+	}
+
+	/// Set the colour palette, starting at index 0.
+	///
+	/// See [`Api::video_set_whole_palette`].
+	pub fn video_set_whole_palette(&self, palette: &[video::RGBColour]) {
+		// SAFETY: the pointer and length come from a valid slice
+		unsafe { (self.0.video_set_whole_palette)(palette.as_ptr(), palette.len()) }
+	}
+}
+
+// ============================================================================
+// Functions
+// ============================================================================
+
+/// Call `get` with the indices `0`, `1`, `2`, etc, until it returns `None`,
+/// yielding each index along with the value found there.
+fn enumerate<T, F>(get: F) -> impl Iterator<Item = (u8, T)>
+where
+	F: Fn(u8) -> Option<T>,
+{
+	(0..=u8::MAX).map_while(move |idx| get(idx).map(|value| (idx, value)))
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::stub;
+
+	#[test]
+	fn serial_loopback() {
+		let api = stub::api();
+		let bios = Bios::new(&api);
+		assert_eq!(bios.serial_write(0, b"Hello", None), Ok(5));
+		assert_eq!(bios.serial_flush(0, Some(Timeout::new_ms(10))), Ok(()));
+		let mut buffer = [0u8; 8];
+		let timeout = Some(Timeout::new_ms(10));
+		assert_eq!(bios.serial_read(0, &mut buffer, timeout), Ok(5));
+		assert_eq!(&buffer[0..5], b"Hello");
+		assert_eq!(bios.serial_read(0, &mut buffer, timeout), Ok(0));
+		assert_eq!(
+			bios.serial_write(2, b"Hello", None),
+			Err(Error::InvalidDevice)
+		);
+		assert_eq!(
+			bios.serial_read(2, &mut buffer, None),
+			Err(Error::InvalidDevice)
+		);
+	}
+
+	#[test]
+	fn serial_configure() {
+		let api = stub::api();
+		let bios = Bios::new(&api);
+		let info = bios.serial_get_info(1).unwrap();
+		assert_eq!(info.name.as_str(), "MIDI");
+		assert!(bios.serial_get_info(2).is_none());
+		let config = serial::Config::new(9600);
+		assert_eq!(bios.serial_configure(0, config.clone()), Ok(()));
+		assert_eq!(
+			bios.serial_configure(1, config.clone()),
+			Err(Error::UnsupportedConfiguration)
+		);
+		assert_eq!(bios.serial_configure(2, config), Err(Error::InvalidDevice));
+	}
+
+	#[test]
+	fn time() {
+		let api = stub::api();
+		let bios = Bios::new(&api);
+		assert_eq!(bios.api_version_get(), crate::API_VERSION);
+		bios.time_clock_set(Time {
+			secs: 1000,
+			nsecs: 5,
+		});
+		let now = bios.time_clock_get();
+		assert_eq!((now.secs, now.nsecs), (1000, 5));
+		let earlier = bios.time_ticks_get();
+		assert!(bios.time_ticks_get() > earlier);
+		assert_eq!(bios.time_ticks_per_second(), Ticks(1000));
+	}
+
+	#[test]
+	fn video_mode() {
+		let api = stub::api();
+		let bios = Bios::new(&api);
+		let text = video::Mode::new(video::Timing::T640x480, video::Format::Text8x16);
+		let graphics = video::Mode::new(video::Timing::T640x480, video::Format::Chunky1);
+		let unsupported = video::Mode::new(video::Timing::T800x600, video::Format::Text8x16);
+		assert!(bios.video_is_valid_mode(graphics));
+		assert!(!bios.video_is_valid_mode(unsupported));
+		assert!(bios.video_mode_needs_vram(graphics));
+		assert!(!bios.video_mode_needs_vram(text));
+
+		let mut vram = [0u32; 640 * 480 / 32];
+		assert_eq!(graphics.frame_size_bytes(), core::mem::size_of_val(&vram));
+		let null = core::ptr::null_mut();
+		assert_eq!(
+			unsafe { bios.video_set_mode(graphics, null) },
+			Err(Error::UnsupportedConfiguration)
+		);
+		assert_eq!(
+			unsafe { bios.video_set_mode(graphics, vram.as_mut_ptr()) },
+			Ok(())
+		);
+		assert_eq!(bios.video_get_mode(), graphics);
+		assert_eq!(bios.video_get_framebuffer(), vram.as_mut_ptr());
+		assert_eq!(
+			unsafe { bios.video_set_mode(unsupported, null) },
+			Err(Error::UnsupportedConfiguration)
+		);
+		assert_eq!(unsafe { bios.video_set_mode(text, null) }, Ok(()));
+		assert_eq!(bios.video_get_mode(), text);
+		bios.video_wait_for_line(0);
+	}
+
+	#[test]
+	fn video_palette() {
+		let api = stub::api();
+		let bios = Bios::new(&api);
+		bios.video_set_palette(2, video::RGBColour::RED);
+		assert_eq!(bios.video_get_palette(2), Some(video::RGBColour::RED));
+		assert_eq!(bios.video_get_palette(16), None);
+		bios.video_set_whole_palette(&[video::RGBColour::GREEN, video::RGBColour::BLUE]);
+		assert_eq!(bios.video_get_palette(0), Some(video::RGBColour::GREEN));
+		assert_eq!(bios.video_get_palette(1), Some(video::RGBColour::BLUE));
+		assert_eq!(bios.video_get_palette(2), Some(video::RGBColour::RED));
+	}
+
+	#[test]
+	fn device_iterators() {
+		let api = stub::api();
+		let bios = Bios::new(&api);
+		let serial_ports = bios
+			.serial_ports()
+			.map(|(id, info)| (id, info.max_data_rate_bps));
+		assert!(serial_ports.eq([(0, 115_200), (1, 31_250)]));
+		let regions = bios
+			.memory_regions()
+			.map(|(id, region)| (id, region.length));
+		assert!(regions.eq([(0, 0x4_0000), (1, 0x20_0000), (2, 0x1000), (3, 0x1_0000)]));
+		let i2c_buses = bios.i2c_buses().map(|(id, info)| (id, info.max_speed_hz));
+		assert!(i2c_buses.eq([(0, 400_000), (1, 100_000)]));
+		let channels = bios
+			.audio_mixer_channels()
+			.map(|(id, info)| (id, info.direction));
+		let output = audio::Direction::Output.make_ffi_safe();
+		let input = audio::Direction::Input.make_ffi_safe();
+		assert!(channels.eq([(0, output), (1, input)]));
+		let peripherals = bios
+			.bus_peripherals()
+			.map(|(id, info)| (id, info.interrupt_bit.into()));
+		assert!(peripherals.eq([(0, Some(0)), (1, None)]));
+		let block_devices = bios.block_devices().map(|(id, info)| (id, info.num_blocks));
+		assert!(block_devices.eq([(0, 65536)]));
+	}
+
+	#[test]
+	fn enumerate_all_indices() {
+		assert_eq!(enumerate(Some).count(), 256);
+		assert_eq!(enumerate(Some).last(), Some((255, 255)));
+	}
+
+	#[test]
+	fn configuration() {
+		let api = stub::api();
+		let bios = Bios::new(&api);
+		let mut buffer = [0u8; 16];
+		assert_eq!(bios.configuration_get(&mut buffer), Ok(0));
+		assert_eq!(bios.configuration_set(b"neotron"), Ok(()));
+		assert_eq!(bios.configuration_get(&mut buffer), Ok(7));
+		assert_eq!(&buffer[0..7], b"neotron");
+		assert_eq!(bios.configuration_get_max_len(), 32);
+		assert_eq!(
+			bios.configuration_set(&[0xFF; 33]),
+			Err(Error::UnsupportedConfiguration)
+		);
+		assert_eq!(bios.configuration_get(&mut buffer), Ok(7));
+	}
+
+	#[test]
+	fn configuration_truncated() {
+		let api = stub::api();
+		let bios = Bios::new(&api);
+		assert_eq!(bios.configuration_set(b"neotron"), Ok(()));
+		let mut buffer = [0u8; 4];
+		let len = bios.configuration_get(&mut buffer).unwrap();
+		assert!(len > buffer.len());
+		assert_eq!(&buffer, b"neot");
+		assert_eq!(bios.configuration_get(&mut []), Ok(7));
+	}
+
+	#[test]
+	fn i2c_read_reg() {
+		let api = stub::api();
+		let bios = Bios::new(&api);
+		let mut out = [0u8; 3];
+		assert_eq!(bios.i2c_read_reg(0, 0x50, 4, &mut out), Ok(()));
+		assert_eq!(out, [0x14, 0x15, 0x16]);
+		assert_eq!(
+			bios.i2c_read_reg(0, 0x50, 6, &mut out),
+			Err(Error::DeviceError)
+		);
+		assert_eq!(
+			bios.i2c_read_reg(0, 0x51, 0, &mut out),
+			Err(Error::DeviceError)
+		);
+		assert_eq!(
+			bios.i2c_read_reg(2, 0x50, 0, &mut out),
+			Err(Error::InvalidDevice)
+		);
+	}
+
+	#[test]
+	fn i2c_write_reg() {
+		let api = stub::api();
+		let bios = Bios::new(&api);
+		assert_eq!(bios.i2c_write_reg(0, 0x50, 2, &[0xAA, 0xBB]), Ok(()));
+		let mut out = [0u8; 4];
+		assert_eq!(bios.i2c_read_reg(0, 0x50, 1, &mut out), Ok(()));
+		assert_eq!(out, [0x11, 0xAA, 0xBB, 0x14]);
+		assert_eq!(
+			bios.i2c_write_reg(0, 0x50, 7, &[0xAA, 0xBB]),
+			Err(Error::DeviceError)
+		);
+		assert_eq!(
+			bios.i2c_write_reg(1, 0x50, 2, &[0xAA, 0xBB]),
+			Err(Error::DeviceError)
+		);
+	}
+
+	#[test]
+	fn ram_regions() {
+		let api = stub::api();
+		let bios = Bios::new(&api);
+		let starts = bios.ram_regions().map(|region| region.start as usize);
+		assert!(starts.eq([0x2000_0000, 0x2100_0000]));
+		assert_eq!(bios.total_ram_bytes(), 0x5_0000);
+	}
+}
+
+// ============================================================================
+// End of File
+// ============================================================================